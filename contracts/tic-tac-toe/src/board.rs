@@ -0,0 +1,211 @@
+//! Bitboards for square and cube boards of any supported size.
+//!
+//! A `Game` stores one bitset per player as `Bytes`, where bit `p % 8` of byte
+//! `p / 8` is set when that player holds position `p`. Positions number the
+//! cells row by row, so the cell in `row` and `column` is `row * size + column`.
+//! Cubes stack `size` such layers, putting the cell at column `x`, row `y` and
+//! layer `z` on position `(z * size + y) * size + x`.
+//!
+//! `Board` copies both bitsets into fixed buffers in a single host call each,
+//! so placing marks and scanning lines costs no further host calls.
+use soroban_sdk::{Bytes, Env, Vec};
+
+use crate::{BoardShape, Player, MAX_BOARD_SIZE, MAX_CUBE_SIZE};
+
+const MAX_SQUARE_CELLS: u32 = MAX_BOARD_SIZE * MAX_BOARD_SIZE;
+const MAX_CUBE_CELLS: u32 = MAX_CUBE_SIZE * MAX_CUBE_SIZE * MAX_CUBE_SIZE;
+const MAX_BOARD_BYTES: usize = if MAX_SQUARE_CELLS > MAX_CUBE_CELLS {
+    MAX_SQUARE_CELLS.div_ceil(8) as usize
+} else {
+    MAX_CUBE_CELLS.div_ceil(8) as usize
+};
+
+/// Layer, row and column steps of the thirteen line directions through a
+/// cube. The first four stay within a layer: across, down and both diagonals,
+/// which are all a square board needs.
+const DIRECTIONS: [(i32, i32, i32); 13] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (0, 1, 1),
+    (0, 1, -1),
+    (1, 0, 0),
+    (1, 0, 1),
+    (1, 0, -1),
+    (1, 1, 0),
+    (1, -1, 0),
+    (1, 1, 1),
+    (1, 1, -1),
+    (1, -1, 1),
+    (1, -1, -1),
+];
+const SQUARE_DIRECTIONS: usize = 4;
+
+pub(crate) struct Board {
+    size: u32,
+    layers: u32, // 1 for square boards, `size` for cubes
+    x: [u8; MAX_BOARD_BYTES],
+    o: [u8; MAX_BOARD_BYTES],
+}
+
+impl Board {
+    pub(crate) fn empty(shape: &BoardShape, size: u32) -> Self {
+        let layers = match shape {
+            BoardShape::Square => 1,
+            BoardShape::Cube => size,
+        };
+        Board {
+            size,
+            layers,
+            x: [0; MAX_BOARD_BYTES],
+            o: [0; MAX_BOARD_BYTES],
+        }
+    }
+
+    pub(crate) fn load(shape: &BoardShape, size: u32, board_x: &Bytes, board_o: &Bytes) -> Self {
+        let mut board = Self::empty(shape, size);
+        let len = board.byte_len();
+        board_x.copy_into_slice(&mut board.x[..len]);
+        board_o.copy_into_slice(&mut board.o[..len]);
+        board
+    }
+
+    /// The X and O bitsets, as stored on `Game`
+    pub(crate) fn to_bytes(&self, env: &Env) -> (Bytes, Bytes) {
+        let len = self.byte_len();
+        (
+            Bytes::from_slice(env, &self.x[..len]),
+            Bytes::from_slice(env, &self.o[..len]),
+        )
+    }
+
+    pub(crate) fn cells(&self) -> u32 {
+        self.size * self.size * self.layers
+    }
+
+    pub(crate) fn get(&self, position: u32) -> Option<Player> {
+        let (byte, mask) = Self::bit(position);
+        if self.x[byte] & mask != 0 {
+            Some(Player::X)
+        } else if self.o[byte] & mask != 0 {
+            Some(Player::O)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn set(&mut self, position: u32, player: &Player) {
+        let (byte, mask) = Self::bit(position);
+        match player {
+            Player::X => self.x[byte] |= mask,
+            Player::O => self.o[byte] |= mask,
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        let taken: u32 = self.x[..self.byte_len()]
+            .iter()
+            .zip(self.o.iter())
+            .map(|(x, o)| (x | o).count_ones())
+            .sum();
+        taken == self.cells()
+    }
+
+    /// The run of `player`'s marks through `position`, if some direction has
+    /// at least `win_length` of them in a row. Only lines through `position`
+    /// are scanned, so call this with the cell that was just played.
+    pub(crate) fn line_through(
+        &self,
+        env: &Env,
+        position: u32,
+        player: &Player,
+        win_length: u32,
+    ) -> Option<Vec<u32>> {
+        let (size, layers) = (self.size as i32, self.layers as i32);
+        let cell = (
+            position as i32 / (size * size),
+            position as i32 / size % size,
+            position as i32 % size,
+        );
+        let index = |(layer, row, column): (i32, i32, i32)| (layer * size + row) * size + column;
+        let holds = |(layer, row, column): (i32, i32, i32)| {
+            (0..layers).contains(&layer)
+                && (0..size).contains(&row)
+                && (0..size).contains(&column)
+                && self.get(index((layer, row, column)) as u32).as_ref() == Some(player)
+        };
+
+        let directions = if self.layers == 1 {
+            &DIRECTIONS[..SQUARE_DIRECTIONS]
+        } else {
+            &DIRECTIONS[..]
+        };
+        for (layer_step, row_step, column_step) in directions {
+            let step = |(layer, row, column): (i32, i32, i32), n: i32| {
+                (
+                    layer + n * layer_step,
+                    row + n * row_step,
+                    column + n * column_step,
+                )
+            };
+
+            // Walk back to the start of the run, then count forwards along it
+            let mut start = cell;
+            while holds(step(start, -1)) {
+                start = step(start, -1);
+            }
+            let mut length = 0;
+            while holds(step(start, length)) {
+                length += 1;
+            }
+
+            if length as u32 >= win_length {
+                let mut line = Vec::new(env);
+                for i in 0..length {
+                    line.push_back(index(step(start, i)) as u32);
+                }
+                return Some(line);
+            }
+        }
+
+        None
+    }
+
+    /// The block holding `position` when a square board is split into squares of
+    /// `block_size`, and the cell of that block it falls on. Blocks and the
+    /// cells within them are both numbered row by row.
+    pub(crate) fn block_of(&self, block_size: u32, position: u32) -> (u32, u32) {
+        let (row, column) = (position / self.size, position % self.size);
+        let blocks_per_row = self.size / block_size;
+        (
+            row / block_size * blocks_per_row + column / block_size,
+            row % block_size * block_size + column % block_size,
+        )
+    }
+
+    /// The position of `cell` within `block`, the inverse of `block_of`
+    pub(crate) fn block_position(&self, block_size: u32, block: u32, cell: u32) -> u32 {
+        let blocks_per_row = self.size / block_size;
+        let row = block / blocks_per_row * block_size + cell / block_size;
+        let column = block % blocks_per_row * block_size + cell % block_size;
+        row * self.size + column
+    }
+
+    /// A copy of one block as a board of its own
+    pub(crate) fn block(&self, block_size: u32, block: u32) -> Board {
+        let mut sub_board = Board::empty(&BoardShape::Square, block_size);
+        for cell in 0..block_size * block_size {
+            if let Some(player) = self.get(self.block_position(block_size, block, cell)) {
+                sub_board.set(cell, &player);
+            }
+        }
+        sub_board
+    }
+
+    fn byte_len(&self) -> usize {
+        self.cells().div_ceil(8) as usize
+    }
+
+    fn bit(position: u32) -> (usize, u8) {
+        ((position / 8) as usize, 1 << (position % 8))
+    }
+}
@@ -0,0 +1,194 @@
+//! Contract events published for indexers.
+//!
+//! Every event has a short symbol as its first topic and the subject of the
+//! transition (a game ID, a user address or the acting admin) as its second topic:
+//!
+//! | Event      | Topics                    | Data                               |
+//! |------------|---------------------------|------------------------------------|
+//! | `created`  | `("created", game_id)`    | `player_x: Address`                |
+//! | `invited`  | `("invited", game_id)`    | `opponent: Address`                |
+//! | `declined` | `("declined", game_id)`   | `opponent: Address`                |
+//! | `joined`   | `("joined", game_id)`     | `player_o: Address`                |
+//! | `move`     | `("move", game_id)`       | `(player: Address, position: u32)` |
+//! | `sub_board`| `("sub_board", game_id)`  | `(sub_board: u32, GameStatus)`     |
+//! | `wild_move`| `("wild_move", game_id)`  | `(player, position, symbol: Player)`|
+//! | `finished` | `("finished", game_id)`   | `status: GameStatus`               |
+//! | `bet_lock` | `("bet_lock", game_id)`   | `(player, amount: i128, TokenType)`|
+//! | `claimed`  | `("claimed", game_id)`    | `(player: Address, amount: i128)`  |
+//! | `cancelled`| `("cancelled", game_id)`  | `(player_x: Address, refund: i128)`|
+//! | `drawoffer`| `("drawoffer", game_id)`  | `player: Address`                  |
+//! | `drawdecl` | `("drawdecl", game_id)`   | `player: Address`                  |
+//! | `rm_offer` | `("rm_offer", game_id)`   | `player: Address`                  |
+//! | `rm_cancel`| `("rm_cancel", game_id)`  | `player: Address`                  |
+//! | `rematch`  | `("rematch", game_id)`    | `(player, new_game_id: u32)`       |
+//! | `deposit`  | `("deposit", user)`       | `(token: Address, amount: i128)`   |
+//! | `withdraw` | `("withdraw", user)`      | `(token: Address, amount: i128)`   |
+//! | `admin`    | `("admin", old_admin)`    | `new_admin: Address`               |
+//! | `upgraded` | `("upgraded", admin)`     | `new_wasm_hash: BytesN<32>`        |
+//! | `migrated` | `("migrated", admin)`     | `schema_version: u32`              |
+//! | `fee`      | `("fee", game_id)`        | `(token: Address, fee: i128)`      |
+//! | `fees_set` | `("fees_set", admin)`     | `FeeConfig`                        |
+//! | `fee_wdraw`| `("fee_wdraw", to)`       | `(token: Address, amount: i128)`   |
+//! | `paused`   | `("paused", admin)`       | `PauseFlags`                       |
+use soroban_sdk::{symbol_short, Address, BytesN, Env};
+
+use crate::{FeeConfig, GameStatus, PauseFlags, Player, TokenType};
+
+pub(crate) fn game_created(env: &Env, game_id: u32, player_x: &Address) {
+    env.events()
+        .publish((symbol_short!("created"), game_id), player_x.clone());
+}
+
+pub(crate) fn player_invited(env: &Env, game_id: u32, opponent: &Address) {
+    env.events()
+        .publish((symbol_short!("invited"), game_id), opponent.clone());
+}
+
+pub(crate) fn invite_declined(env: &Env, game_id: u32, opponent: &Address) {
+    env.events()
+        .publish((symbol_short!("declined"), game_id), opponent.clone());
+}
+
+pub(crate) fn player_joined(env: &Env, game_id: u32, player_o: &Address) {
+    env.events()
+        .publish((symbol_short!("joined"), game_id), player_o.clone());
+}
+
+pub(crate) fn move_made(env: &Env, game_id: u32, player: &Address, position: u32) {
+    env.events()
+        .publish((symbol_short!("move"), game_id), (player.clone(), position));
+}
+
+pub(crate) fn sub_board_closed(env: &Env, game_id: u32, sub_board: u32, status: &GameStatus) {
+    env.events().publish(
+        (symbol_short!("sub_board"), game_id),
+        (sub_board, status.clone()),
+    );
+}
+
+pub(crate) fn wild_move_made(
+    env: &Env,
+    game_id: u32,
+    player: &Address,
+    position: u32,
+    symbol: &Player,
+) {
+    env.events().publish(
+        (symbol_short!("wild_move"), game_id),
+        (player.clone(), position, symbol.clone()),
+    );
+}
+
+pub(crate) fn game_finished(env: &Env, game_id: u32, status: &GameStatus) {
+    env.events()
+        .publish((symbol_short!("finished"), game_id), status.clone());
+}
+
+pub(crate) fn bet_locked(
+    env: &Env,
+    game_id: u32,
+    player: &Address,
+    amount: i128,
+    token_type: &TokenType,
+) {
+    env.events().publish(
+        (symbol_short!("bet_lock"), game_id),
+        (player.clone(), amount, token_type.clone()),
+    );
+}
+
+pub(crate) fn rewards_claimed(env: &Env, game_id: u32, player: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("claimed"), game_id),
+        (player.clone(), amount),
+    );
+}
+
+pub(crate) fn game_cancelled(env: &Env, game_id: u32, player_x: &Address, refund: i128) {
+    env.events().publish(
+        (symbol_short!("cancelled"), game_id),
+        (player_x.clone(), refund),
+    );
+}
+
+pub(crate) fn draw_offered(env: &Env, game_id: u32, player: &Address) {
+    env.events()
+        .publish((symbol_short!("drawoffer"), game_id), player.clone());
+}
+
+pub(crate) fn draw_declined(env: &Env, game_id: u32, player: &Address) {
+    env.events()
+        .publish((symbol_short!("drawdecl"), game_id), player.clone());
+}
+
+pub(crate) fn rematch_offered(env: &Env, game_id: u32, player: &Address) {
+    env.events()
+        .publish((symbol_short!("rm_offer"), game_id), player.clone());
+}
+
+pub(crate) fn rematch_cancelled(env: &Env, game_id: u32, player: &Address) {
+    env.events()
+        .publish((symbol_short!("rm_cancel"), game_id), player.clone());
+}
+
+pub(crate) fn rematch_accepted(env: &Env, game_id: u32, player: &Address, new_game_id: u32) {
+    env.events().publish(
+        (symbol_short!("rematch"), game_id),
+        (player.clone(), new_game_id),
+    );
+}
+
+pub(crate) fn deposit(env: &Env, user: &Address, token: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("deposit"), user.clone()),
+        (token.clone(), amount),
+    );
+}
+
+pub(crate) fn withdrawal(env: &Env, user: &Address, token: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("withdraw"), user.clone()),
+        (token.clone(), amount),
+    );
+}
+
+pub(crate) fn admin_changed(env: &Env, old_admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (symbol_short!("admin"), old_admin.clone()),
+        new_admin.clone(),
+    );
+}
+
+pub(crate) fn upgraded(env: &Env, admin: &Address, new_wasm_hash: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("upgraded"), admin.clone()),
+        new_wasm_hash.clone(),
+    );
+}
+
+pub(crate) fn migrated(env: &Env, admin: &Address, schema_version: u32) {
+    env.events()
+        .publish((symbol_short!("migrated"), admin.clone()), schema_version);
+}
+
+pub(crate) fn fee_charged(env: &Env, game_id: u32, token: &Address, fee: i128) {
+    env.events()
+        .publish((symbol_short!("fee"), game_id), (token.clone(), fee));
+}
+
+pub(crate) fn fees_set(env: &Env, admin: &Address, fees: &FeeConfig) {
+    env.events()
+        .publish((symbol_short!("fees_set"), admin.clone()), fees.clone());
+}
+
+pub(crate) fn fees_withdrawn(env: &Env, to: &Address, token: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("fee_wdraw"), to.clone()),
+        (token.clone(), amount),
+    );
+}
+
+pub(crate) fn pause_changed(env: &Env, admin: &Address, paused: &PauseFlags) {
+    env.events()
+        .publish((symbol_short!("paused"), admin.clone()), paused.clone());
+}
@@ -1,13 +1,164 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Env, Symbol, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env,
+    Symbol, Vec,
+};
+
+mod board;
+mod events;
+mod migration;
+
+use board::Board;
+
+/// Version of the storage layout written by this build of the contract
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Largest number of games rewritten by a single `migrate` call
+pub const MAX_MIGRATION_BATCH: u32 = 20;
+
+/// Fees are expressed in basis points of the payout (1/100th of a percent)
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Highest fee the admin can configure (10%)
+pub const MAX_FEE_BPS: u32 = 1_000;
+
+/// Move deadline used when a game is created without an explicit timeout (24 hours)
+pub const DEFAULT_MOVE_TIMEOUT: u64 = 24 * 60 * 60;
+
+/// Largest number of games returned by a single `list_games_page` call
+pub const MAX_PAGE_LIMIT: u32 = 20;
+
+/// Largest number of game IDs examined by a single `list_games_page` call.
+/// Each ID may read a `Game` and a `GameBet`, so this keeps a page well
+/// inside the per-transaction ledger read limit.
+pub const MAX_PAGE_SCAN: u32 = 40;
+
+/// Largest number of index entries examined by a single `get_player_games`
+/// call. Each entry also reads the indexed `Game` and `GameBet`, so fewer
+/// fit in a page than with `list_games_page`.
+pub const MAX_PLAYER_PAGE_SCAN: u32 = 25;
+
+/// Ledgers closed per day at roughly five seconds per ledger
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// TTL of the contract instance (configuration and game counter)
+pub const INSTANCE_TTL: u32 = 30 * DAY_IN_LEDGERS;
+
+/// TTL of per-player entries (escrow balances and game index)
+pub const PLAYER_DATA_TTL: u32 = 90 * DAY_IN_LEDGERS;
+
+/// TTL of games that are in progress or still hold unclaimed bets
+pub const ACTIVE_GAME_TTL: u32 = 30 * DAY_IN_LEDGERS;
+
+/// TTL of finished games with nothing left to claim
+pub const SETTLED_GAME_TTL: u32 = 7 * DAY_IN_LEDGERS;
+
+/// Side of the classic board, used when a game does not choose one
+pub const DEFAULT_BOARD_SIZE: u32 = 3;
+
+/// Smallest and largest supported board sides
+pub const MIN_BOARD_SIZE: u32 = 3;
+pub const MAX_BOARD_SIZE: u32 = 15;
+
+/// Default win length on boards larger than 5x5 (Gomoku style); smaller
+/// boards default to a full row
+pub const DEFAULT_MAX_WIN_LENGTH: u32 = 5;
+
+/// Sides of the supported cube boards: 3x3x3 and 4x4x4 (Qubic)
+pub const MIN_CUBE_SIZE: u32 = 3;
+pub const MAX_CUBE_SIZE: u32 = 4;
+
+/// Side of an Ultimate board: a 3x3 grid of 3x3 sub-boards
+pub const ULTIMATE_BOARD_SIZE: u32 = 9;
+pub const SUB_BOARD_SIZE: u32 = 3;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum DataKey {
     Game(u32),
-    GameCounter,
-    Balance(Symbol),        // User balances by symbol
+    GameCounter,            // Next game ID, kept in instance storage
+    Balance(Address),       // User balances by address
     GameBet(u32),          // Betting info for each game
+    NativeToken,           // Stellar Asset Contract for native XLM
+    Admin,                 // Address allowed to upgrade the contract
+    SchemaVersion,         // Storage layout version (see SCHEMA_VERSION)
+    MigrationCursor,       // Next game ID to rewrite while a migration is running
+    FeeConfig,             // Fee rates applied to new betting games
+    FeeBalance(Address),   // Fees accrued to the treasury, by token contract
+    Paused,                // PauseFlags currently in force
+    PlayerGameCount(Address),  // Number of entries in a player's game index
+    PlayerGame(Address, u32),  // Game ID at a position of a player's game index
+    RematchOffer(u32),     // Pending rematch offer on a finished game
+    GameMoves(u32),        // Moves played in a game, in order (kept from schema 2 on)
+    UltimateState(u32),    // Sub-board progress of an Ultimate game
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[contracterror]
+#[repr(u32)]
+pub enum Error {
+    // Escrow
+    AmountMustBePositive = 1,
+    InsufficientBalance = 2,
+    InsufficientTokenBalance = 3,
+    TokenNotFound = 4,
+    NativeTokenNotConfigured = 5,
+    // Game lifecycle
+    GameNotFound = 10,
+    GameAlreadyHasTwoPlayers = 11,
+    CannotJoinOwnGame = 12,
+    GameNeedsSecondPlayer = 13,
+    GameAlreadyFinished = 14,
+    NotYourTurn = 15,
+    InvalidPosition = 16,
+    PositionAlreadyTaken = 17,
+    // Betting
+    BetAmountMustBePositive = 20,
+    InsufficientNativeBalanceForBet = 21,
+    InsufficientTokenBalanceForBet = 22,
+    GameBetNotFound = 23,
+    PlayerOAlreadyPaid = 24,
+    GameHasNoBetting = 25,
+    GameStillInProgress = 26,
+    NotAPlayer = 27,
+    RewardsAlreadyClaimed = 28,
+    PlayerCannotClaim = 29,
+    // Timeouts
+    InvalidMoveTimeout = 30,
+    MoveDeadlineNotReached = 31,
+    CannotClaimTimeoutOnOwnTurn = 32,
+    // Cancellation
+    GameCancelled = 40,
+    NotGameCreator = 41,
+    GameAlreadyStarted = 42,
+    // Draw and rematch offers
+    NoDrawOffer = 50,
+    InvalidOffer = 51, // Repeating a pending draw offer, answering your own offer or rematching a game twice
+    // Listing
+    InvalidPageLimit = 60,
+    // Administration
+    AdminNotSet = 70,
+    // Storage migration
+    MigrationRequired = 80,
+    SchemaVersionMismatch = 81,
+    InvalidBatchSize = 82,
+    // Platform fees
+    InvalidFee = 90,
+    InsufficientFeeBalance = 91,
+    // Pausing
+    Paused = 100,
+    // Invitations
+    NotInvited = 110,
+    // Private games
+    JoinCodeRequired = 120,
+    InvalidJoinCode = 121,
+    // Rematches
+    NoRematchOffer = 130,
+    // Board configuration
+    InvalidBoardSize = 140,
+    InvalidWinLength = 141,
+    // Game mode rules
+    IllegalMove = 150, // The move breaks a rule of the game's mode, such as Ultimate's sub-board or Wild's symbol
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -17,6 +168,60 @@ pub enum Player {
     O,
 }
 
+/// A creation-time choice of mark, settled when player O joins
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub enum SideChoice {
+    #[default]
+    X,
+    O,
+    Random,
+}
+
+impl SideChoice {
+    fn resolve(&self, env: &Env) -> Player {
+        match self {
+            SideChoice::X => Player::X,
+            SideChoice::O => Player::O,
+            SideChoice::Random => {
+                if env.prng().gen_range::<u64>(0..=1) == 0 {
+                    Player::X
+                } else {
+                    Player::O
+                }
+            }
+        }
+    }
+}
+
+impl From<Player> for SideChoice {
+    fn from(player: Player) -> Self {
+        match player {
+            Player::X => SideChoice::X,
+            Player::O => SideChoice::O,
+        }
+    }
+}
+
+/// Shape of the board a game is played on
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub enum BoardShape {
+    #[default]
+    Square, // size x size cells
+    Cube,   // size x size x size cells; lines run in any of 13 directions
+}
+
+/// Rules a game is played under
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub enum GameMode {
+    #[default]
+    Classic,  // `win_length` in a row on a single board
+    Ultimate, // Nine sub-boards; the cell played picks the opponent's next sub-board
+    Wild,     // Each turn the mover places X or O; a completed line counts for the mover
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum GameStatus {
@@ -25,20 +230,41 @@ pub enum GameStatus {
     OWins,
     Draw,
     Claimed,               // Rewards have been claimed
+    Cancelled,             // Creator cancelled before anyone joined
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum TokenType {
     Native,                // XLM
-    Stellar(Symbol),       // Stellar Asset Contract Address as Symbol
+    Stellar(Address),      // Stellar Asset Contract address
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct UserBalance {
     pub native: i128,      // XLM balance
-    pub tokens: Vec<(Symbol, i128)>, // Token balances
+    pub tokens: Vec<(Address, i128)>, // Token balances
+}
+
+/// Platform fee rates in basis points
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeConfig {
+    pub win_fee_bps: u32,  // Taken from the winner's payout
+    pub draw_fee_bps: u32, // Taken from each player's refund in a draw
+}
+
+/// Entry points the admin has switched off; each flag covers one group
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct PauseFlags {
+    pub new_games: bool,   // create_game, create_game_with_bet
+    pub joins: bool,       // join_game
+    pub moves: bool,       // make_move, claim_timeout_win, resign and draw offers
+    pub deposits: bool,    // deposit_native, deposit_token
+    pub withdrawals: bool, // withdraw_native, withdraw_token, withdraw_fees
+    pub claims: bool,      // claim_rewards, cancel_game, decline_invite
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -51,30 +277,143 @@ pub struct GameBet {
     pub rewards_claimed: bool,
     pub player_x_claimed: bool,
     pub player_o_claimed: bool,
+    pub fees: FeeConfig, // Rates in force when the game was created
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct Game {
-    pub board: Vec<Option<Player>>,
+    pub mode: GameMode,
+    pub misere: bool, // Completing a line loses instead of wins
+    pub shape: BoardShape,
+    pub size: u32,       // Board is size x size cells, numbered row by row
+    pub win_length: u32, // Marks in a row needed to win
+    pub board_x: Bytes,  // Bitset of the positions X holds (bit p % 8 of byte p / 8)
+    pub board_o: Bytes,  // Bitset of the positions O holds
     pub current_player: Player,
-    pub player_x: Symbol,
-    pub player_o: Symbol,
+    pub player_x: Address,
+    pub player_o: Option<Address>, // None while waiting for a second player
     pub status: GameStatus,
     pub has_bet: bool,     // Whether this game has betting enabled
+    pub move_timeout: u64, // Seconds the player on move has before forfeiting
+    pub last_move_timestamp: u64, // Ledger timestamp of the last move (or join)
+    pub last_move_ledger: u32,    // Ledger sequence of the last move (or join)
+    pub draw_offer: Option<Address>, // Player with a pending draw offer
+    pub invited: Option<Address>, // Only this player may join, if set
+    pub join_code_hash: Option<BytesN<32>>, // sha256 of the secret needed to join, if private
+    pub creator_side: SideChoice, // Mark the creator plays; never Random once player O joins
+    pub first_move: SideChoice,   // Mark that moves first; never Random once player O joins
+    pub previous_game_id: Option<u32>, // Game this one is a rematch of
+    pub rematch_game_id: Option<u32>,  // Rematch started from this game, once accepted
+    pub winning_line: Vec<u32>, // Positions of the completed line (the loser's under misère rules); empty unless decided on the board
+}
+
+/// Sub-board progress of an Ultimate game, stored beside its `Game`.
+/// Sub-boards are numbered row by row, like the cells within each of them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct UltimateState {
+    pub won_x: Bytes, // Bitset of the sub-boards X has won, laid out like `Game.board_x`
+    pub won_o: Bytes, // Bitset of the sub-boards O has won
+    pub closed: u32,  // Bit b set once sub-board b is won or full
+    pub next_board: Option<u32>, // Sub-board the next move must be played in; None for a free move
+}
+
+/// One move of a game, as returned by `get_moves`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Move {
+    pub player: Address,
+    pub position: u32,
+    pub symbol: Player,  // Mark placed; always the mover's own outside Wild games
+    pub ledger: u32,     // Ledger sequence the move was made in
+    pub timestamp: u64,  // Ledger timestamp the move was made at
+}
+
+/// Optional settings chosen by the creator of a game
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct GameOptions {
+    pub move_timeout: Option<u64>, // Defaults to DEFAULT_MOVE_TIMEOUT
+    pub opponent: Option<Address>, // Invite a specific player; anyone may join if unset
+    pub join_code_hash: Option<BytesN<32>>, // Make the game private, joinable with the matching secret
+    pub creator_side: SideChoice, // Defaults to X
+    pub first_move: SideChoice,   // Defaults to X
+    pub size: Option<u32>,        // Defaults to DEFAULT_BOARD_SIZE
+    pub win_length: Option<u32>,  // Defaults to the side, capped at DEFAULT_MAX_WIN_LENGTH
+    pub mode: GameMode,           // Defaults to Classic; Ultimate fixes the size and win length
+    pub misere: bool,             // Play misère rules, where completing a line loses
+    pub shape: BoardShape,        // Defaults to Square; cubes are MIN_CUBE_SIZE to MAX_CUBE_SIZE wide
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct GameInfo {
     pub id: u32,
-    pub player_x: Symbol,
-    pub player_o: Symbol,
+    pub player_x: Address,
+    pub player_o: Option<Address>,
     pub status: GameStatus,
     pub has_bet: bool,
     pub bet_amount: i128,
     pub bet_token_native: bool,    // true for native XLM, false for stellar token
-    pub bet_token_symbol: Symbol,  // token symbol if not native
+    pub bet_token: Option<Address>, // token contract if not native
+    pub invited: Option<Address>,   // invited opponent, if the game is invite-only
+    pub private: bool,              // joinable only with a secret code
+    pub creator_side: SideChoice,   // Random until player O joins
+    pub first_move: SideChoice,     // Random until player O joins
+    pub size: u32,
+    pub win_length: u32,
+    pub mode: GameMode,
+    pub misere: bool,
+    pub shape: BoardShape,
+}
+
+/// How a rematch is set up, chosen by the player offering it
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct RematchOptions {
+    pub keep_sides: bool, // Players swap X and O unless set
+    pub roll_over: bool,  // Pay out the finished game's bet and lock the new stake from it
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RematchOffer {
+    pub offered_by: Address,
+    pub options: RematchOptions,
+}
+
+/// Criteria for `list_games_page`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GameFilter {
+    pub statuses: Vec<GameStatus>,   // Empty matches any status except Cancelled
+    pub open_only: bool,             // Only games still waiting for player O
+    pub has_bet: Option<bool>,
+    pub token_types: Vec<TokenType>, // Empty matches any bet token
+    pub min_bet: Option<i128>,
+    pub max_bet: Option<i128>,
+}
+
+impl GameFilter {
+    /// Filter matching every game that `list_games` would return
+    pub fn any(env: &Env) -> Self {
+        GameFilter {
+            statuses: Vec::new(env),
+            open_only: false,
+            has_bet: None,
+            token_types: Vec::new(env),
+            min_bet: None,
+            max_bet: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct GamePage {
+    pub games: Vec<GameInfo>,
+    pub next_cursor: Option<u32>, // Pass as `start_after` to fetch the next page
 }
 
 #[contract]
@@ -82,140 +421,331 @@ pub struct TicTacToeContract;
 
 #[contractimpl]
 impl TicTacToeContract {
+    /// Initialize the contract with its admin and the native XLM Stellar Asset Contract address
+    pub fn __constructor(env: Env, admin: Address, native_token: Address) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::NativeToken, &native_token);
+        storage.set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        Self::bump_instance(&env);
+    }
+
+    // === ADMINISTRATION ===
+
+    /// Get the address allowed to upgrade the contract
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        Self::admin(&env)
+    }
+
+    /// Hand the admin role to `new_admin`; requires the current admin's auth
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        Self::bump_instance(&env);
+
+        events::admin_changed(&env, &admin, &new_admin);
+        Ok(())
+    }
+
+    /// Replace the contract code with previously uploaded WASM, keeping the
+    /// contract ID and all of its storage; requires the admin's auth
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+
+        Self::bump_instance(&env);
+        events::upgraded(&env, &admin, &new_wasm_hash);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Storage layout version of the data held by this instance
+    pub fn get_schema_version(env: Env) -> u32 {
+        Self::schema_version(&env)
+    }
+
+    /// Rewrite up to `batch_size` games stored in the `from_version` layout
+    /// into the next version, continuing where the previous call stopped.
+    /// Once every game is rewritten the schema version is bumped; run again
+    /// with the new version until it reaches `SCHEMA_VERSION`. Games cannot
+    /// be created or played while a migration is pending. Returns the number
+    /// of games left in the current pass; requires the admin's auth.
+    pub fn migrate(env: Env, from_version: u32, batch_size: u32) -> Result<u32, Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+
+        if from_version != Self::schema_version(&env) || from_version >= SCHEMA_VERSION {
+            return Err(Error::SchemaVersionMismatch);
+        }
+
+        if batch_size == 0 || batch_size > MAX_MIGRATION_BATCH {
+            return Err(Error::InvalidBatchSize);
+        }
+
+        let storage = env.storage().instance();
+        let game_counter: u32 = storage.get(&DataKey::GameCounter).unwrap_or(0);
+        let mut game_id: u32 = storage.get(&DataKey::MigrationCursor).unwrap_or(1);
+        let batch_end = game_counter.min(game_id.saturating_add(batch_size - 1));
+
+        while game_id <= batch_end {
+            migration::migrate_game(&env, from_version, game_id);
+            game_id += 1;
+        }
+
+        let remaining = game_counter.saturating_add(1) - game_id;
+        if remaining == 0 {
+            storage.remove(&DataKey::MigrationCursor);
+            storage.set(&DataKey::SchemaVersion, &(from_version + 1));
+            events::migrated(&env, &admin, from_version + 1);
+        } else {
+            storage.set(&DataKey::MigrationCursor, &game_id);
+        }
+        Self::bump_instance(&env);
+
+        Ok(remaining)
+    }
+
+    /// Switch groups of entry points off or back on; requires the admin's
+    /// auth. Withdrawals have their own flag so players can take out settled
+    /// balances while betting stays paused.
+    pub fn set_paused(env: Env, paused: PauseFlags) -> Result<(), Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+        Self::bump_instance(&env);
+
+        events::pause_changed(&env, &admin, &paused);
+        Ok(())
+    }
+
+    /// Entry points currently switched off
+    pub fn get_paused(env: Env) -> PauseFlags {
+        Self::pause_flags(&env)
+    }
+
     // === ESCROW MANAGEMENT ===
     
     /// Deposit native XLM to user's balance
-    pub fn deposit_native(env: Env, user: Symbol, amount: i128) {
+    pub fn deposit_native(env: Env, user: Address, amount: i128) -> Result<(), Error> {
+        user.require_auth();
+        Self::require_unpaused(&env, |paused| paused.deposits)?;
+
         if amount <= 0 {
-            panic!("Amount must be positive");
+            return Err(Error::AmountMustBePositive);
         }
         
+        // Pull the funds into the contract before crediting the balance
+        let native_token = Self::native_token(&env)?;
+        token::Client::new(&env, &native_token)
+            .transfer(&user, &env.current_contract_address(), &amount);
+
         let mut balance = Self::get_user_balance(&env, &user);
         balance.native += amount;
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::Balance(user), &balance);
+        Self::save_balance(&env, &user, &balance);
+
+        events::deposit(&env, &user, &native_token, amount);
+        Ok(())
     }
     
     /// Deposit tokens to user's balance
-    pub fn deposit_token(env: Env, user: Symbol, token_address: Symbol, amount: i128) {
+    pub fn deposit_token(env: Env, user: Address, token_address: Address, amount: i128) -> Result<(), Error> {
+        user.require_auth();
+        Self::require_unpaused(&env, |paused| paused.deposits)?;
+
         if amount <= 0 {
-            panic!("Amount must be positive");
+            return Err(Error::AmountMustBePositive);
         }
         
+        token::Client::new(&env, &token_address)
+            .transfer(&user, &env.current_contract_address(), &amount);
+
         let mut balance = Self::get_user_balance(&env, &user);
         
         // Find existing token balance or add new one
         let mut found = false;
         for i in 0..balance.tokens.len() {
-            let (symbol, bal) = balance.tokens.get(i).unwrap();
-            if symbol == token_address {
-                balance.tokens.set(i, (symbol, bal + amount));
+            let (token, bal) = balance.tokens.get(i).unwrap();
+            if token == token_address {
+                balance.tokens.set(i, (token, bal + amount));
                 found = true;
                 break;
             }
         }
         
         if !found {
-            balance.tokens.push_back((token_address, amount));
+            balance.tokens.push_back((token_address.clone(), amount));
         }
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::Balance(user), &balance);
+        Self::save_balance(&env, &user, &balance);
+
+        events::deposit(&env, &user, &token_address, amount);
+        Ok(())
     }
     
     /// Withdraw native XLM from user's balance
-    pub fn withdraw_native(env: Env, user: Symbol, amount: i128) {
+    pub fn withdraw_native(env: Env, user: Address, amount: i128) -> Result<(), Error> {
+        user.require_auth();
+        Self::require_unpaused(&env, |paused| paused.withdrawals)?;
+
         if amount <= 0 {
-            panic!("Amount must be positive");
+            return Err(Error::AmountMustBePositive);
         }
         
         let mut balance = Self::get_user_balance(&env, &user);
         
         if balance.native < amount {
-            panic!("Insufficient balance");
+            return Err(Error::InsufficientBalance);
         }
         
         balance.native -= amount;
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::Balance(user), &balance);
+        Self::save_balance(&env, &user, &balance);
+
+        let native_token = Self::native_token(&env)?;
+        token::Client::new(&env, &native_token)
+            .transfer(&env.current_contract_address(), &user, &amount);
+
+        events::withdrawal(&env, &user, &native_token, amount);
+        Ok(())
     }
     
     /// Withdraw tokens from user's balance
-    pub fn withdraw_token(env: Env, user: Symbol, token_address: Symbol, amount: i128) {
+    pub fn withdraw_token(env: Env, user: Address, token_address: Address, amount: i128) -> Result<(), Error> {
+        user.require_auth();
+        Self::require_unpaused(&env, |paused| paused.withdrawals)?;
+
         if amount <= 0 {
-            panic!("Amount must be positive");
+            return Err(Error::AmountMustBePositive);
         }
         
         let mut balance = Self::get_user_balance(&env, &user);
         
         for i in 0..balance.tokens.len() {
-            let (symbol, bal) = balance.tokens.get(i).unwrap();
-            if symbol == token_address {
+            let (token, bal) = balance.tokens.get(i).unwrap();
+            if token == token_address {
                 if bal < amount {
-                    panic!("Insufficient token balance");
+                    return Err(Error::InsufficientTokenBalance);
                 }
-                balance.tokens.set(i, (symbol, bal - amount));
+                balance.tokens.set(i, (token, bal - amount));
                 
-                env.storage()
-                    .persistent()
-                    .set(&DataKey::Balance(user), &balance);
-                return;
+                Self::save_balance(&env, &user, &balance);
+
+                token::Client::new(&env, &token_address)
+                    .transfer(&env.current_contract_address(), &user, &amount);
+
+                events::withdrawal(&env, &user, &token_address, amount);
+                return Ok(());
             }
         }
         
-        panic!("Token not found in balance");
+        Err(Error::TokenNotFound)
     }
     
     /// Get user's balance
-    pub fn get_balance(env: Env, user: Symbol) -> UserBalance {
+    pub fn get_balance(env: Env, user: Address) -> UserBalance {
         Self::get_user_balance(&env, &user)
     }
     
+    /// Get the native XLM Stellar Asset Contract used for native deposits
+    pub fn get_native_token(env: Env) -> Result<Address, Error> {
+        Self::native_token(&env)
+    }
+    
+    // === PLATFORM FEES ===
+
+    /// Fee rates applied to betting games created from now on
+    pub fn get_fees(env: Env) -> FeeConfig {
+        Self::fee_config(&env)
+    }
+
+    /// Set the fee rates for new betting games; games already created keep
+    /// the rates they started with. Requires the admin's auth.
+    pub fn set_fees(env: Env, win_fee_bps: u32, draw_fee_bps: u32) -> Result<(), Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+
+        if win_fee_bps > MAX_FEE_BPS || draw_fee_bps > MAX_FEE_BPS {
+            return Err(Error::InvalidFee);
+        }
+
+        let fees = FeeConfig { win_fee_bps, draw_fee_bps };
+        env.storage().instance().set(&DataKey::FeeConfig, &fees);
+        Self::bump_instance(&env);
+
+        events::fees_set(&env, &admin, &fees);
+        Ok(())
+    }
+
+    /// Fees accrued to the treasury in `token`
+    pub fn get_fee_balance(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FeeBalance(token))
+            .unwrap_or(0)
+    }
+
+    /// Pay out accrued fees in `token` to `to`; requires the admin's auth
+    pub fn withdraw_fees(env: Env, token: Address, to: Address, amount: i128) -> Result<(), Error> {
+        let admin = Self::admin(&env)?;
+        admin.require_auth();
+        Self::require_unpaused(&env, |paused| paused.withdrawals)?;
+
+        if amount <= 0 {
+            return Err(Error::AmountMustBePositive);
+        }
+
+        let key = DataKey::FeeBalance(token.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if balance < amount {
+            return Err(Error::InsufficientFeeBalance);
+        }
+
+        env.storage().persistent().set(&key, &(balance - amount));
+        Self::extend_persistent(&env, &key, PLAYER_DATA_TTL);
+
+        token::Client::new(&env, &token)
+            .transfer(&env.current_contract_address(), &to, &amount);
+
+        events::fees_withdrawn(&env, &to, &token, amount);
+        Ok(())
+    }
+
     // === GAME MANAGEMENT ===
     
     /// Create a game without betting
-    pub fn create_game(env: Env, player_x: Symbol) -> u32 {
-        Self::create_game_internal(&env, player_x, None, None)
+    pub fn create_game(env: Env, player_x: Address, options: GameOptions) -> Result<u32, Error> {
+        player_x.require_auth();
+        Self::require_unpaused(&env, |paused| paused.new_games)?;
+
+        Self::create_game_internal(&env, player_x, &options, None, None)
     }
     
     /// Create a game with betting
-    pub fn create_game_with_bet(env: Env, player_x: Symbol, bet_amount: i128, token_type: TokenType) -> u32 {
+    pub fn create_game_with_bet(
+        env: Env,
+        player_x: Address,
+        bet_amount: i128,
+        token_type: TokenType,
+        options: GameOptions,
+    ) -> Result<u32, Error> {
+        player_x.require_auth();
+        Self::require_unpaused(&env, |paused| paused.new_games)?;
+
         if bet_amount <= 0 {
-            panic!("Bet amount must be positive");
+            return Err(Error::BetAmountMustBePositive);
         }
         
-        // Check if player has sufficient balance
-        let balance = Self::get_user_balance(&env, &player_x);
-        match &token_type {
-            TokenType::Native => {
-                if balance.native < bet_amount {
-                    panic!("Insufficient native balance for bet");
-                }
-            }
-            TokenType::Stellar(token_symbol) => {
-                let mut has_balance = false;
-                for i in 0..balance.tokens.len() {
-                    let (symbol, bal) = balance.tokens.get(i).unwrap();
-                    if symbol == *token_symbol && bal >= bet_amount {
-                        has_balance = true;
-                        break;
-                    }
-                }
-                if !has_balance {
-                    panic!("Insufficient token balance for bet");
-                }
-            }
-        }
+        Self::require_bet_balance(&env, &player_x, bet_amount, &token_type)?;
         
-        let game_id = Self::create_game_internal(&env, player_x.clone(), Some(bet_amount), Some(token_type.clone()));
+        let game_id = Self::create_game_internal(&env, player_x.clone(), &options, Some(bet_amount), Some(token_type.clone()))?;
         
         // Lock the bet amount from player X
         Self::lock_bet(&env, &player_x, bet_amount, &token_type);
+        events::bet_locked(&env, game_id, &player_x, bet_amount, &token_type);
         
         // Create betting info
         let game_bet = GameBet {
@@ -226,171 +756,323 @@ impl TicTacToeContract {
             rewards_claimed: false,
             player_x_claimed: false,
             player_o_claimed: false,
+            fees: Self::fee_config(&env),
         };
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::GameBet(game_id), &game_bet);
+        Self::save_game_bet(&env, game_id, &game_bet);
         
-        game_id
+        Ok(game_id)
     }
     
-    fn create_game_internal(env: &Env, player_x: Symbol, bet_amount: Option<i128>, _token_type: Option<TokenType>) -> u32 {
+    fn create_game_internal(
+        env: &Env,
+        player_x: Address,
+        options: &GameOptions,
+        bet_amount: Option<i128>,
+        _token_type: Option<TokenType>,
+    ) -> Result<u32, Error> {
+        Self::require_current_schema(env)?;
+
+        let move_timeout = options.move_timeout.unwrap_or(DEFAULT_MOVE_TIMEOUT);
+        if move_timeout == 0 {
+            return Err(Error::InvalidMoveTimeout);
+        }
+
+        if options.opponent.as_ref() == Some(&player_x) {
+            return Err(Error::CannotJoinOwnGame);
+        }
+
+        let (size, win_length) = match (&options.mode, &options.shape) {
+            (GameMode::Classic | GameMode::Wild, BoardShape::Square) => {
+                let size = options.size.unwrap_or(DEFAULT_BOARD_SIZE);
+                if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+                    return Err(Error::InvalidBoardSize);
+                }
+                let win_length = options.win_length.unwrap_or(size.min(DEFAULT_MAX_WIN_LENGTH));
+                if !(MIN_BOARD_SIZE..=size).contains(&win_length) {
+                    return Err(Error::InvalidWinLength);
+                }
+                (size, win_length)
+            }
+            (GameMode::Classic | GameMode::Wild, BoardShape::Cube) => {
+                let size = options.size.unwrap_or(MIN_CUBE_SIZE);
+                if !(MIN_CUBE_SIZE..=MAX_CUBE_SIZE).contains(&size) {
+                    return Err(Error::InvalidBoardSize);
+                }
+                // Lines on a cube always run from face to face
+                if options.win_length.is_some_and(|win_length| win_length != size) {
+                    return Err(Error::InvalidWinLength);
+                }
+                (size, size)
+            }
+            (GameMode::Ultimate, BoardShape::Cube) => return Err(Error::InvalidBoardSize),
+            (GameMode::Ultimate, BoardShape::Square) => {
+                if options.size.is_some_and(|size| size != ULTIMATE_BOARD_SIZE) {
+                    return Err(Error::InvalidBoardSize);
+                }
+                if options.win_length.is_some_and(|win_length| win_length != SUB_BOARD_SIZE) {
+                    return Err(Error::InvalidWinLength);
+                }
+                (ULTIMATE_BOARD_SIZE, SUB_BOARD_SIZE)
+            }
+        };
+        let (board_x, board_o) = Board::empty(&options.shape, size).to_bytes(env);
+
         let game_counter = env
             .storage()
-            .persistent()
+            .instance()
             .get(&DataKey::GameCounter)
             .unwrap_or(0u32);
         let new_game_id = game_counter + 1;
 
-        let mut board = Vec::new(env);
-        for _ in 0..9 {
-            board.push_back(None);
-        }
-
         let game = Game {
-            board,
-            current_player: Player::X,
+            mode: options.mode.clone(),
+            misere: options.misere,
+            shape: options.shape.clone(),
+            size,
+            win_length,
+            board_x,
+            board_o,
+            current_player: Player::X, // Settled from first_move when player O joins
             player_x,
-            player_o: symbol_short!("waiting"),
+            player_o: None,
             status: GameStatus::InProgress,
             has_bet: bet_amount.is_some(),
+            move_timeout,
+            last_move_timestamp: env.ledger().timestamp(),
+            last_move_ledger: env.ledger().sequence(),
+            draw_offer: None,
+            invited: options.opponent.clone(),
+            join_code_hash: options.join_code_hash.clone(),
+            creator_side: options.creator_side.clone(),
+            first_move: options.first_move.clone(),
+            previous_game_id: None,
+            rematch_game_id: None,
+            winning_line: Vec::new(env),
         };
 
         env.storage()
-            .persistent()
-            .set(&DataKey::Game(new_game_id), &game);
-        env.storage()
-            .persistent()
+            .instance()
             .set(&DataKey::GameCounter, &new_game_id);
+        if game.mode == GameMode::Ultimate {
+            let (won_x, won_o) = Board::empty(&BoardShape::Square, SUB_BOARD_SIZE).to_bytes(env);
+            let state = UltimateState { won_x, won_o, closed: 0, next_board: None };
+            env.storage().persistent().set(&DataKey::UltimateState(new_game_id), &state);
+        }
+        Self::save_game(env, new_game_id, &game);
 
-        new_game_id
+        Self::index_player_game(env, &game.player_x, new_game_id);
+
+        events::game_created(env, new_game_id, &game.player_x);
+        // Invitees are indexed when they join, so nobody can grow another
+        // player's index just by inviting them
+        if let Some(invited) = &game.invited {
+            events::player_invited(env, new_game_id, invited);
+        }
+        Ok(new_game_id)
     }
 
     /// Join a game (handles both betting and non-betting games)
-    pub fn join_game(env: Env, game_id: u32, player_o: Symbol) -> Game {
-        let mut game: Game = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .expect("Game not found");
+    pub fn join_game(env: Env, game_id: u32, player_o: Address) -> Result<Game, Error> {
+        player_o.require_auth();
+        Self::require_unpaused(&env, |paused| paused.joins)?;
+
+        Self::join_game_internal(env, game_id, player_o, None)
+    }
+
+    /// Join a private game, proving knowledge of the creator's secret code
+    pub fn join_game_with_code(env: Env, game_id: u32, player_o: Address, secret: Bytes) -> Result<Game, Error> {
+        player_o.require_auth();
+        Self::require_unpaused(&env, |paused| paused.joins)?;
+
+        Self::join_game_internal(env, game_id, player_o, Some(secret))
+    }
+
+    fn join_game_internal(env: Env, game_id: u32, player_o: Address, secret: Option<Bytes>) -> Result<Game, Error> {
+        let mut game = Self::load_game(&env, game_id)?;
+
+        if game.status == GameStatus::Cancelled {
+            return Err(Error::GameCancelled);
+        }
 
-        if game.player_o != symbol_short!("waiting") {
-            panic!("Game already has two players");
+        if game.player_o.is_some() {
+            return Err(Error::GameAlreadyHasTwoPlayers);
         }
 
         if game.player_x == player_o {
-            panic!("Cannot join your own game");
+            return Err(Error::CannotJoinOwnGame);
+        }
+
+        if game.invited.as_ref().is_some_and(|invited| *invited != player_o) {
+            return Err(Error::NotInvited);
+        }
+
+        match (&game.join_code_hash, secret) {
+            (None, None) => {}
+            (Some(_), None) => return Err(Error::JoinCodeRequired),
+            (Some(hash), Some(secret)) if env.crypto().sha256(&secret).to_bytes() == *hash => {}
+            _ => return Err(Error::InvalidJoinCode),
         }
 
         // Handle betting if this game has a bet
         if game.has_bet {
-            let mut game_bet: GameBet = env
-                .storage()
-                .persistent()
-                .get(&DataKey::GameBet(game_id))
-                .expect("Game bet not found");
+            let mut game_bet = Self::load_game_bet(&env, game_id)?;
 
             if game_bet.player_o_paid {
-                panic!("Player O has already paid");
+                return Err(Error::PlayerOAlreadyPaid);
             }
 
-            // Check if player O has sufficient balance
-            let balance = Self::get_user_balance(&env, &player_o);
-            match &game_bet.token_type {
-                TokenType::Native => {
-                    if balance.native < game_bet.amount {
-                        panic!("Insufficient native balance for bet");
-                    }
-                }
-                TokenType::Stellar(token_symbol) => {
-                    let mut has_balance = false;
-                    for i in 0..balance.tokens.len() {
-                        let (symbol, bal) = balance.tokens.get(i).unwrap();
-                        if symbol == *token_symbol && bal >= game_bet.amount {
-                            has_balance = true;
-                            break;
-                        }
-                    }
-                    if !has_balance {
-                        panic!("Insufficient token balance for bet");
-                    }
-                }
-            }
+            Self::require_bet_balance(&env, &player_o, game_bet.amount, &game_bet.token_type)?;
 
             // Lock the bet amount from player O
             Self::lock_bet(&env, &player_o, game_bet.amount, &game_bet.token_type);
+            events::bet_locked(&env, game_id, &player_o, game_bet.amount, &game_bet.token_type);
             
             // Update betting info
             game_bet.player_o_paid = true;
-            env.storage()
-                .persistent()
-                .set(&DataKey::GameBet(game_id), &game_bet);
+            Self::save_game_bet(&env, game_id, &game_bet);
         }
 
-        game.player_o = player_o;
+        Self::index_player_game(&env, &player_o, game_id);
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Game(game_id), &game);
+        events::player_joined(&env, game_id, &player_o);
+
+        // Random choices are settled only once both players are committed.
+        // The creator waits in the X seat, so swap seats if they play O.
+        let creator_side = game.creator_side.resolve(&env);
+        let first_move = game.first_move.resolve(&env);
+        if creator_side == Player::O {
+            game.player_o = Some(core::mem::replace(&mut game.player_x, player_o));
+        } else {
+            game.player_o = Some(player_o);
+        }
+        game.creator_side = creator_side.into();
+        game.current_player = first_move.clone();
+        game.first_move = first_move.into();
+
+        // The first move deadline runs from the moment the game becomes playable
+        Self::touch_last_move(&env, &mut game);
+
+        Self::save_game(&env, game_id, &game);
         
-        game
+        Ok(game)
     }
     
-    /// Claim rewards after game ends
-    pub fn claim_rewards(env: Env, game_id: u32, player: Symbol) {
-        let game: Game = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .expect("Game not found");
+    /// Cancel a game nobody has joined yet, refunding the creator's locked bet
+    pub fn cancel_game(env: Env, game_id: u32, player_x: Address) -> Result<Game, Error> {
+        player_x.require_auth();
+        Self::require_unpaused(&env, |paused| paused.claims)?;
 
-        if !game.has_bet {
-            panic!("Game has no betting");
+        let mut game = Self::load_game(&env, game_id)?;
+
+        if game.player_x != player_x {
+            return Err(Error::NotGameCreator);
         }
 
-        if game.status == GameStatus::InProgress {
-            panic!("Game is still in progress");
+        if game.status == GameStatus::Cancelled {
+            return Err(Error::GameCancelled);
         }
 
-        if game.player_x != player && game.player_o != player {
-            panic!("Not a player in this game");
+        if game.player_o.is_some() {
+            return Err(Error::GameAlreadyStarted);
         }
 
-        let mut game_bet: GameBet = env
-            .storage()
-            .persistent()
-            .get(&DataKey::GameBet(game_id))
-            .expect("Game bet not found");
+        Self::cancel_unjoined(&env, game_id, &mut game)?;
+        Ok(game)
+    }
 
-        // Check if this specific player has already claimed
-        let is_player_x = game.player_x == player;
-        if (is_player_x && game_bet.player_x_claimed) || (!is_player_x && game_bet.player_o_claimed) {
-            panic!("Rewards already claimed");
-        }
+    /// Turn down an invitation, cancelling the game and refunding player X's bet
+    pub fn decline_invite(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.claims)?;
 
-        let can_claim = match game.status {
-            GameStatus::XWins => is_player_x,
-            GameStatus::OWins => !is_player_x,
-            GameStatus::Draw => true, // Both players can claim in a draw
-            _ => false,
-        };
+        let mut game = Self::load_game(&env, game_id)?;
 
-        if !can_claim {
-            panic!("Player cannot claim rewards");
+        if game.invited != Some(player.clone()) {
+            return Err(Error::NotInvited);
         }
 
-        let reward_amount = match game.status {
-            GameStatus::XWins | GameStatus::OWins => game_bet.amount * 2, // Winner gets both bets
-            GameStatus::Draw => game_bet.amount, // Each player gets their bet back
-            _ => panic!("Invalid game status for claiming"),
-        };
+        if game.status == GameStatus::Cancelled {
+            return Err(Error::GameCancelled);
+        }
 
-        // Give rewards to player
-        Self::unlock_bet(&env, &player, reward_amount, &game_bet.token_type);
+        if game.player_o.is_some() {
+            return Err(Error::GameAlreadyStarted);
+        }
 
-        // Mark this specific player as having claimed
-        if is_player_x {
+        events::invite_declined(&env, game_id, &player);
+        Self::cancel_unjoined(&env, game_id, &mut game)?;
+        Ok(game)
+    }
+    
+    /// Claim rewards after game ends
+    pub fn claim_rewards(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.claims)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        Self::claim_internal(&env, game_id, &game, &player)
+    }
+
+    fn claim_internal(env: &Env, game_id: u32, game: &Game, player: &Address) -> Result<(), Error> {
+        if !game.has_bet {
+            return Err(Error::GameHasNoBetting);
+        }
+
+        if game.status == GameStatus::InProgress {
+            return Err(Error::GameStillInProgress);
+        }
+
+        if game.status == GameStatus::Cancelled {
+            return Err(Error::GameCancelled);
+        }
+
+        if game.player_x != *player && game.player_o.as_ref() != Some(player) {
+            return Err(Error::NotAPlayer);
+        }
+
+        let mut game_bet = Self::load_game_bet(env, game_id)?;
+
+        // Check if this specific player has already claimed
+        let is_player_x = game.player_x == *player;
+        if (is_player_x && game_bet.player_x_claimed) || (!is_player_x && game_bet.player_o_claimed) {
+            return Err(Error::RewardsAlreadyClaimed);
+        }
+
+        // The status names the winner, who under misère rules is the player
+        // that avoided completing a line
+        let can_claim = match game.status {
+            GameStatus::XWins => is_player_x,
+            GameStatus::OWins => !is_player_x,
+            GameStatus::Draw => true, // Both players can claim in a draw
+            _ => false,
+        };
+
+        if !can_claim {
+            return Err(Error::PlayerCannotClaim);
+        }
+
+        let (payout, fee_bps) = match game.status {
+            GameStatus::Draw => (game_bet.amount, game_bet.fees.draw_fee_bps), // Each player gets their bet back
+            _ => (game_bet.amount * 2, game_bet.fees.win_fee_bps),             // Winner gets both bets
+        };
+
+        // Fees round down, in the player's favour
+        let fee = payout * fee_bps as i128 / BPS_DENOMINATOR;
+        let reward_amount = payout - fee;
+
+        // Give rewards to player
+        Self::unlock_bet(env, player, reward_amount, &game_bet.token_type);
+        events::rewards_claimed(env, game_id, player, reward_amount);
+
+        if fee > 0 {
+            let token = Self::token_address(env, &game_bet.token_type)?;
+            Self::accrue_fee(env, &token, fee);
+            events::fee_charged(env, game_id, &token, fee);
+        }
+
+        // Mark this specific player as having claimed
+        if is_player_x {
             game_bet.player_x_claimed = true;
         } else {
             game_bet.player_o_claimed = true;
@@ -407,79 +1089,470 @@ impl TicTacToeContract {
             }
         }
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::GameBet(game_id), &game_bet);
+        Self::save_game_bet(env, game_id, &game_bet);
+
+        Ok(())
     }
     
     // === HELPER FUNCTIONS ===
     
-    fn get_user_balance(env: &Env, user: &Symbol) -> UserBalance {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Balance(user.clone()))
-            .unwrap_or(UserBalance {
+    fn get_user_balance(env: &Env, user: &Address) -> UserBalance {
+        let key = DataKey::Balance(user.clone());
+        match env.storage().persistent().get(&key) {
+            Some(balance) => {
+                Self::extend_persistent(env, &key, PLAYER_DATA_TTL);
+                balance
+            }
+            None => UserBalance {
                 native: 0,
                 tokens: Vec::new(env),
-            })
+            },
+        }
+    }
+
+    fn save_balance(env: &Env, user: &Address, balance: &UserBalance) {
+        let key = DataKey::Balance(user.clone());
+        env.storage().persistent().set(&key, balance);
+        Self::extend_persistent(env, &key, PLAYER_DATA_TTL);
+        Self::bump_instance(env);
+    }
+    
+    fn admin(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)
+    }
+    
+    fn schema_version(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(0)
+    }
+
+    fn require_current_schema(env: &Env) -> Result<(), Error> {
+        if Self::schema_version(env) < SCHEMA_VERSION {
+            return Err(Error::MigrationRequired);
+        }
+        Ok(())
+    }
+    
+    fn pause_flags(env: &Env) -> PauseFlags {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or_default()
+    }
+
+    fn require_unpaused(env: &Env, scope: impl Fn(&PauseFlags) -> bool) -> Result<(), Error> {
+        if scope(&Self::pause_flags(env)) {
+            return Err(Error::Paused);
+        }
+        Ok(())
+    }
+
+    fn fee_config(env: &Env) -> FeeConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeConfig)
+            .unwrap_or_default()
+    }
+
+    fn accrue_fee(env: &Env, token: &Address, fee: i128) {
+        let key = DataKey::FeeBalance(token.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + fee));
+        Self::extend_persistent(env, &key, PLAYER_DATA_TTL);
+    }
+
+    /// Token contract holding the funds for `token_type`
+    fn token_address(env: &Env, token_type: &TokenType) -> Result<Address, Error> {
+        match token_type {
+            TokenType::Native => Self::native_token(env),
+            TokenType::Stellar(token_address) => Ok(token_address.clone()),
+        }
+    }
+    
+    fn native_token(env: &Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::NativeToken)
+            .ok_or(Error::NativeTokenNotConfigured)
+    }
+    
+    fn game_info(env: &Env, id: u32, game: Game) -> GameInfo {
+        let (has_bet, bet_amount, bet_token_native, bet_token) = if game.has_bet {
+            if let Some(game_bet) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, GameBet>(&DataKey::GameBet(id))
+            {
+                let (is_native, token) = match game_bet.token_type {
+                    TokenType::Native => (true, None),
+                    TokenType::Stellar(token_address) => (false, Some(token_address)),
+                };
+                (true, game_bet.amount, is_native, token)
+            } else {
+                (false, 0, true, None)
+            }
+        } else {
+            (false, 0, true, None)
+        };
+
+        GameInfo {
+            id,
+            player_x: game.player_x,
+            player_o: game.player_o,
+            status: game.status,
+            has_bet,
+            bet_amount,
+            bet_token_native,
+            bet_token,
+            invited: game.invited,
+            private: game.join_code_hash.is_some(),
+            creator_side: game.creator_side,
+            first_move: game.first_move,
+            size: game.size,
+            win_length: game.win_length,
+            mode: game.mode,
+            misere: game.misere,
+            shape: game.shape,
+        }
+    }
+
+    fn matches_filter(info: &GameInfo, filter: &GameFilter) -> bool {
+        let status_ok = if filter.statuses.is_empty() {
+            info.status != GameStatus::Cancelled
+        } else {
+            filter.statuses.contains(&info.status)
+        };
+        if !status_ok {
+            return false;
+        }
+
+        let open = info.player_o.is_none()
+            && info.invited.is_none()
+            && !info.private
+            && info.status == GameStatus::InProgress;
+        if filter.open_only && !open {
+            return false;
+        }
+
+        if let Some(has_bet) = filter.has_bet {
+            if info.has_bet != has_bet {
+                return false;
+            }
+        }
+
+        // Token and amount criteria only make sense for games with a bet
+        let wants_bet = !filter.token_types.is_empty()
+            || filter.min_bet.is_some()
+            || filter.max_bet.is_some();
+        if wants_bet {
+            if !info.has_bet {
+                return false;
+            }
+            if !filter.token_types.is_empty() {
+                let token_type = match &info.bet_token {
+                    Some(token) => TokenType::Stellar(token.clone()),
+                    None => TokenType::Native,
+                };
+                if !filter.token_types.contains(&token_type) {
+                    return false;
+                }
+            }
+            if filter.min_bet.is_some_and(|min| info.bet_amount < min) {
+                return false;
+            }
+            if filter.max_bet.is_some_and(|max| info.bet_amount > max) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Side of `player` in a game that is still being played
+    fn active_side(game: &Game, player: &Address) -> Result<Player, Error> {
+        if game.status != GameStatus::InProgress {
+            return Err(Error::GameAlreadyFinished);
+        }
+
+        let player_o = match &game.player_o {
+            Some(player_o) => player_o,
+            None => return Err(Error::GameNeedsSecondPlayer),
+        };
+
+        if *player == game.player_x {
+            Ok(Player::X)
+        } else if player == player_o {
+            Ok(Player::O)
+        } else {
+            Err(Error::NotAPlayer)
+        }
+    }
+
+    /// Clear the pending draw offer when `player` is the side it was made to
+    fn answer_draw_offer(game: &mut Game, player: &Address) -> Result<(), Error> {
+        Self::active_side(game, player)?;
+
+        match &game.draw_offer {
+            None => return Err(Error::NoDrawOffer),
+            Some(offered_by) if offered_by == player => return Err(Error::InvalidOffer),
+            Some(_) => {}
+        }
+
+        game.draw_offer = None;
+        Ok(())
+    }
+
+    fn opponent(player: &Player) -> Player {
+        match player {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+
+    fn win_status(winner: &Player) -> GameStatus {
+        match winner {
+            Player::X => GameStatus::XWins,
+            Player::O => GameStatus::OWins,
+        }
+    }
+
+    /// Check that `player` took part in a finished game that has not been rematched yet
+    fn require_rematchable(game: &Game, player: &Address) -> Result<(), Error> {
+        match game.status {
+            GameStatus::InProgress => return Err(Error::GameStillInProgress),
+            GameStatus::Cancelled => return Err(Error::GameCancelled),
+            _ => {}
+        }
+
+        if game.player_x != *player && game.player_o.as_ref() != Some(player) {
+            return Err(Error::NotAPlayer);
+        }
+
+        if game.rematch_game_id.is_some() {
+            return Err(Error::InvalidOffer);
+        }
+
+        Ok(())
+    }
+
+    fn require_bet_balance(env: &Env, player: &Address, amount: i128, token_type: &TokenType) -> Result<(), Error> {
+        let balance = Self::get_user_balance(env, player);
+        match token_type {
+            TokenType::Native => {
+                if balance.native < amount {
+                    return Err(Error::InsufficientNativeBalanceForBet);
+                }
+            }
+            TokenType::Stellar(token_address) => {
+                let mut has_balance = false;
+                for i in 0..balance.tokens.len() {
+                    let (token, bal) = balance.tokens.get(i).unwrap();
+                    if token == *token_address && bal >= amount {
+                        has_balance = true;
+                        break;
+                    }
+                }
+                if !has_balance {
+                    return Err(Error::InsufficientTokenBalanceForBet);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel a game nobody has joined, returning player X's locked bet
+    fn cancel_unjoined(env: &Env, game_id: u32, game: &mut Game) -> Result<(), Error> {
+        let mut refund = 0;
+        if game.has_bet {
+            let mut game_bet = Self::load_game_bet(env, game_id)?;
+
+            if game_bet.player_x_paid && !game_bet.player_x_claimed {
+                refund = game_bet.amount;
+                Self::unlock_bet(env, &game.player_x, refund, &game_bet.token_type);
+            }
+
+            game_bet.player_x_claimed = true;
+            game_bet.rewards_claimed = true;
+            Self::save_game_bet(env, game_id, &game_bet);
+        }
+
+        game.status = GameStatus::Cancelled;
+        Self::save_game(env, game_id, game);
+
+        events::game_cancelled(env, game_id, &game.player_x, refund);
+        Ok(())
+    }
+
+    fn touch_last_move(env: &Env, game: &mut Game) {
+        game.last_move_timestamp = env.ledger().timestamp();
+        game.last_move_ledger = env.ledger().sequence();
     }
     
-    fn lock_bet(env: &Env, user: &Symbol, amount: i128, token_type: &TokenType) {
+    fn load_game(env: &Env, game_id: u32) -> Result<Game, Error> {
+        Self::require_current_schema(env)?;
+
+        let game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)?;
+        Self::extend_game_ttl(env, game_id, &game);
+        Ok(game)
+    }
+
+    fn save_game(env: &Env, game_id: u32, game: &Game) {
+        env.storage().persistent().set(&DataKey::Game(game_id), game);
+        Self::extend_game_ttl(env, game_id, game);
+        Self::bump_instance(env);
+    }
+
+    fn save_game_bet(env: &Env, game_id: u32, game_bet: &GameBet) {
+        let key = DataKey::GameBet(game_id);
+        env.storage().persistent().set(&key, game_bet);
+        Self::extend_persistent(env, &key, Self::game_bet_ttl(game_bet));
+    }
+
+    /// Extend a game, its bet, its moves and any Ultimate state, keeping them around for longer while
+    /// anything is still at stake. Settled games are only ever extended to
+    /// `SETTLED_GAME_TTL`, so they expire soon after play stops.
+    fn extend_game_ttl(env: &Env, game_id: u32, game: &Game) {
+        let game_bet: Option<GameBet> = if game.has_bet {
+            env.storage().persistent().get(&DataKey::GameBet(game_id))
+        } else {
+            None
+        };
+
+        let ttl = match (&game.status, &game_bet) {
+            (GameStatus::InProgress, _) => ACTIVE_GAME_TTL,
+            (_, Some(game_bet)) => Self::game_bet_ttl(game_bet),
+            (_, None) => SETTLED_GAME_TTL,
+        };
+
+        Self::extend_persistent(env, &DataKey::Game(game_id), ttl);
+        if game_bet.is_some() {
+            Self::extend_persistent(env, &DataKey::GameBet(game_id), ttl);
+        }
+        for key in [DataKey::GameMoves(game_id), DataKey::UltimateState(game_id)] {
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent(env, &key, ttl);
+            }
+        }
+
+        // The stakes are paid out of the players' balances, so keep those
+        // live for as long as the bet is unsettled
+        if game_bet.is_some_and(|game_bet| !game_bet.rewards_claimed) {
+            let players = [Some(game.player_x.clone()), game.player_o.clone()];
+            for player in players.into_iter().flatten() {
+                let key = DataKey::Balance(player);
+                if env.storage().persistent().has(&key) {
+                    Self::extend_persistent(env, &key, PLAYER_DATA_TTL);
+                }
+            }
+        }
+    }
+
+    fn game_bet_ttl(game_bet: &GameBet) -> u32 {
+        if game_bet.rewards_claimed {
+            SETTLED_GAME_TTL
+        } else {
+            ACTIVE_GAME_TTL
+        }
+    }
+
+    /// Extend `key` to `ttl` ledgers once it has dropped a day below that
+    fn extend_persistent(env: &Env, key: &DataKey, ttl: u32) {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, ttl - DAY_IN_LEDGERS, ttl);
+    }
+
+    fn bump_instance(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_TTL - DAY_IN_LEDGERS, INSTANCE_TTL);
+    }
+    
+    fn load_game_bet(env: &Env, game_id: u32) -> Result<GameBet, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::GameBet(game_id))
+            .ok_or(Error::GameBetNotFound)
+    }
+    
+    /// Append `game_id` to the player's index. Each game gets its own entry,
+    /// so the cost of adding one does not grow with the player's history.
+    fn index_player_game(env: &Env, player: &Address, game_id: u32) {
+        let count_key = DataKey::PlayerGameCount(player.clone());
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+
+        let key = DataKey::PlayerGame(player.clone(), count);
+        env.storage().persistent().set(&key, &game_id);
+        Self::extend_persistent(env, &key, PLAYER_DATA_TTL);
+
+        env.storage().persistent().set(&count_key, &(count + 1));
+        Self::extend_persistent(env, &count_key, PLAYER_DATA_TTL);
+    }
+
+    fn lock_bet(env: &Env, user: &Address, amount: i128, token_type: &TokenType) {
         let mut balance = Self::get_user_balance(env, user);
         
         match token_type {
             TokenType::Native => {
                 balance.native -= amount;
             }
-            TokenType::Stellar(token_symbol) => {
+            TokenType::Stellar(token_address) => {
                 for i in 0..balance.tokens.len() {
-                    let (symbol, bal) = balance.tokens.get(i).unwrap();
-                    if symbol == *token_symbol {
-                        balance.tokens.set(i, (symbol, bal - amount));
+                    let (token, bal) = balance.tokens.get(i).unwrap();
+                    if token == *token_address {
+                        balance.tokens.set(i, (token, bal - amount));
                         break;
                     }
                 }
             }
         }
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::Balance(user.clone()), &balance);
+        Self::save_balance(env, user, &balance);
     }
     
-    fn unlock_bet(env: &Env, user: &Symbol, amount: i128, token_type: &TokenType) {
+    fn unlock_bet(env: &Env, user: &Address, amount: i128, token_type: &TokenType) {
         let mut balance = Self::get_user_balance(env, user);
         
         match token_type {
             TokenType::Native => {
                 balance.native += amount;
             }
-            TokenType::Stellar(token_symbol) => {
+            TokenType::Stellar(token_address) => {
                 let mut found = false;
                 for i in 0..balance.tokens.len() {
-                    let (symbol, bal) = balance.tokens.get(i).unwrap();
-                    if symbol == *token_symbol {
-                        balance.tokens.set(i, (symbol, bal + amount));
+                    let (token, bal) = balance.tokens.get(i).unwrap();
+                    if token == *token_address {
+                        balance.tokens.set(i, (token, bal + amount));
                         found = true;
                         break;
                     }
                 }
                 
                 if !found {
-                    balance.tokens.push_back((token_symbol.clone(), amount));
+                    balance.tokens.push_back((token_address.clone(), amount));
                 }
             }
         }
         
-        env.storage()
-            .persistent()
-            .set(&DataKey::Balance(user.clone()), &balance);
+        Self::save_balance(env, user, &balance);
     }
 
-    pub fn list_games(env: Env) -> Vec<GameInfo> {
+    /// List all games except cancelled ones
+    pub fn list_games(env: Env) -> Result<Vec<GameInfo>, Error> {
+        Self::require_current_schema(&env)?;
+
         let game_counter = env
             .storage()
-            .persistent()
+            .instance()
             .get(&DataKey::GameCounter)
             .unwrap_or(0u32);
 
@@ -491,152 +1564,597 @@ impl TicTacToeContract {
                 .persistent()
                 .get::<DataKey, Game>(&DataKey::Game(i))
             {
-                let (has_bet, bet_amount, bet_token_native, bet_token_symbol) = if game.has_bet {
-                    if let Some(game_bet) = env
-                        .storage()
-                        .persistent()
-                        .get::<DataKey, GameBet>(&DataKey::GameBet(i))
-                    {
-                        let (is_native, token_symbol) = match game_bet.token_type {
-                            TokenType::Native => (true, symbol_short!("XLM")),
-                            TokenType::Stellar(symbol) => (false, symbol),
-                        };
-                        (true, game_bet.amount, is_native, token_symbol)
-                    } else {
-                        (false, 0, true, symbol_short!("XLM"))
-                    }
-                } else {
-                    (false, 0, true, symbol_short!("XLM"))
-                };
+                // Private games are shared out of band and never listed publicly
+                if game.status == GameStatus::Cancelled || game.join_code_hash.is_some() {
+                    continue;
+                }
+
+                games.push_back(Self::game_info(&env, i, game));
+            }
+        }
+
+        Ok(games)
+    }
+
+    /// List games with IDs after `start_after` that match `filter`, one page at a time.
+    ///
+    /// At most `limit` games are returned and at most `MAX_PAGE_SCAN` IDs are
+    /// examined per call, so a page may come back short (or empty) while more
+    /// games remain. Keep calling with `next_cursor` until it is `None`.
+    pub fn list_games_page(
+        env: Env,
+        start_after: u32,
+        limit: u32,
+        filter: GameFilter,
+    ) -> Result<GamePage, Error> {
+        Self::require_current_schema(&env)?;
+        if limit == 0 || limit > MAX_PAGE_LIMIT {
+            return Err(Error::InvalidPageLimit);
+        }
+
+        let game_counter = env
+            .storage()
+            .instance()
+            .get(&DataKey::GameCounter)
+            .unwrap_or(0u32);
+        let scan_end = game_counter.min(start_after.saturating_add(MAX_PAGE_SCAN));
+
+        let mut games = Vec::new(&env);
+        let mut id = start_after;
+
+        while id < scan_end && games.len() < limit {
+            id += 1;
+            if let Some(game) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Game>(&DataKey::Game(id))
+            {
+                if game.join_code_hash.is_some() {
+                    continue;
+                }
+                let info = Self::game_info(&env, id, game);
+                if Self::matches_filter(&info, &filter) {
+                    games.push_back(info);
+                }
+            }
+        }
 
-                games.push_back(GameInfo {
-                    id: i,
-                    player_x: game.player_x,
-                    player_o: game.player_o,
-                    status: game.status,
-                    has_bet,
-                    bet_amount,
-                    bet_token_native,
-                    bet_token_symbol,
-                });
+        let next_cursor = if id < game_counter { Some(id) } else { None };
+
+        Ok(GamePage { games, next_cursor })
+    }
+
+    /// List games `player` created or joined, in that order.
+    ///
+    /// `cursor` is the number of index entries already consumed (0 for the
+    /// first page) and an empty `status_filter` matches every status except
+    /// `Cancelled`. Like `list_games_page`, at most `MAX_PLAYER_PAGE_SCAN`
+    /// entries are examined per call, so keep passing `next_cursor` until it is `None`.
+    pub fn get_player_games(
+        env: Env,
+        player: Address,
+        status_filter: Vec<GameStatus>,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<GamePage, Error> {
+        Self::require_current_schema(&env)?;
+        if limit == 0 || limit > MAX_PAGE_LIMIT {
+            return Err(Error::InvalidPageLimit);
+        }
+
+        let count_key = DataKey::PlayerGameCount(player.clone());
+        let count: u32 = match env.storage().persistent().get(&count_key) {
+            Some(count) => {
+                Self::extend_persistent(&env, &count_key, PLAYER_DATA_TTL);
+                count
+            }
+            None => 0,
+        };
+        let scan_end = count.min(cursor.saturating_add(MAX_PLAYER_PAGE_SCAN));
+        let filter = GameFilter {
+            statuses: status_filter,
+            ..GameFilter::any(&env)
+        };
+
+        let mut games = Vec::new(&env);
+        let mut position = cursor;
+
+        while position < scan_end && games.len() < limit {
+            let key = DataKey::PlayerGame(player.clone(), position);
+            position += 1;
+            let Some(id) = env.storage().persistent().get::<DataKey, u32>(&key) else {
+                continue;
+            };
+            Self::extend_persistent(&env, &key, PLAYER_DATA_TTL);
+            if let Some(game) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Game>(&DataKey::Game(id))
+            {
+                let info = Self::game_info(&env, id, game);
+                if Self::matches_filter(&info, &filter) {
+                    games.push_back(info);
+                }
             }
         }
 
-        games
+        let next_cursor = if position < count { Some(position) } else { None };
+
+        Ok(GamePage { games, next_cursor })
     }
     
     /// Get betting information for a specific game
-    pub fn get_game_bet(env: Env, game_id: u32) -> Option<GameBet> {
-        env.storage()
+    pub fn get_game_bet(env: Env, game_id: u32) -> Result<Option<GameBet>, Error> {
+        Self::require_current_schema(&env)?;
+        Ok(env.storage()
             .persistent()
-            .get(&DataKey::GameBet(game_id))
+            .get(&DataKey::GameBet(game_id)))
+    }
+
+    pub fn make_move(env: Env, game_id: u32, player: Address, position: u32) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        Self::play_move(&env, game_id, game, &player, position, None)
+    }
+
+    /// Make a move in a Wild game, placing whichever mark `symbol` names
+    pub fn make_move_wild(env: Env, game_id: u32, player: Address, position: u32, symbol: Player) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        Self::play_move(&env, game_id, game, &player, position, Some(symbol))
     }
 
-    pub fn make_move(env: Env, game_id: u32, player: Symbol, position: u32) -> Game {
-        if position >= 9 {
-            panic!("Invalid position: must be 0-8");
+    /// Make a move on a cube board, naming the cell by its column `x`, row `y`
+    /// and layer `z`. Coordinates off the cube, or any on a square board, are
+    /// invalid positions.
+    pub fn make_move_cube(env: Env, game_id: u32, player: Address, x: u32, y: u32, z: u32) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        if game.shape != BoardShape::Cube || x >= game.size || y >= game.size || z >= game.size {
+            return Err(Error::InvalidPosition);
         }
 
-        let mut game: Game = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .expect("Game not found");
+        let position = (z * game.size + y) * game.size + x;
+        Self::play_move(&env, game_id, game, &player, position, None)
+    }
 
-        if game.status != GameStatus::InProgress {
-            panic!("Game is already finished");
+    /// Place `symbol`, or the mover's own mark when it is None, on the loaded
+    /// `game` and settle it. Only Wild games take a symbol, and they always need one.
+    fn play_move(
+        env: &Env,
+        game_id: u32,
+        mut game: Game,
+        player: &Address,
+        position: u32,
+        symbol: Option<Player>,
+    ) -> Result<Game, Error> {
+        match (&game.mode, &symbol) {
+            (GameMode::Wild, None) | (GameMode::Classic | GameMode::Ultimate, Some(_)) => {
+                return Err(Error::IllegalMove)
+            }
+            _ => {}
         }
+        let mut board = Board::load(&game.shape, game.size, &game.board_x, &game.board_o);
 
-        if game.player_o == symbol_short!("waiting") {
-            panic!("Game needs a second player");
+        if position >= board.cells() {
+            return Err(Error::InvalidPosition);
+        }
+
+        if game.status != GameStatus::InProgress {
+            return Err(Error::GameAlreadyFinished);
         }
 
+        let player_o = match &game.player_o {
+            Some(player_o) => player_o.clone(),
+            None => return Err(Error::GameNeedsSecondPlayer),
+        };
+
         let expected_player = match game.current_player {
             Player::X => game.player_x.clone(),
-            Player::O => game.player_o.clone(),
+            Player::O => player_o,
         };
 
-        if player != expected_player {
-            panic!("Not your turn");
+        if *player != expected_player {
+            return Err(Error::NotYourTurn);
         }
 
-        if game.board.get(position).unwrap().is_some() {
-            panic!("Position already taken");
+        if board.get(position).is_some() {
+            return Err(Error::PositionAlreadyTaken);
         }
 
-        game.board.set(position, Some(game.current_player.clone()));
+        let symbol = symbol.unwrap_or(game.current_player.clone());
+        board.set(position, &symbol);
+        (game.board_x, game.board_o) = board.to_bytes(env);
+        game.draw_offer = None; // Playing on implicitly declines a pending offer
 
-        game.status = Self::check_winner(&game.board);
+        let (status, line) = match game.mode {
+            GameMode::Classic | GameMode::Wild => Self::check_winner(
+                env,
+                &board,
+                position,
+                &symbol,
+                &game.current_player,
+                game.win_length,
+                game.misere,
+            ),
+            GameMode::Ultimate => {
+                Self::play_ultimate(env, game_id, &board, position, &game.current_player, game.misere)?
+            }
+        };
+        game.status = status;
+        if let Some(line) = line {
+            game.winning_line = line;
+        }
+        Self::touch_last_move(env, &mut game);
+        Self::record_move(env, game_id, player, position, &symbol);
+        if game.mode == GameMode::Wild {
+            events::wild_move_made(env, game_id, player, position, &symbol);
+        } else {
+            events::move_made(env, game_id, player, position);
+        }
 
         if game.status == GameStatus::InProgress {
-            game.current_player = match game.current_player {
-                Player::X => Player::O,
-                Player::O => Player::X,
-            };
+            game.current_player = Self::opponent(&game.current_player);
+        } else {
+            events::game_finished(env, game_id, &game.status);
         }
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Game(game_id), &game);
-        game
+        Self::save_game(env, game_id, &game);
+        Ok(game)
     }
 
-    pub fn get_game(env: Env, game_id: u32) -> Game {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .expect("Game not found")
+    /// Claim the win when the opponent has let their move deadline pass
+    pub fn claim_timeout_win(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        let claimant = Self::active_side(&game, &player)?;
+
+        // Only the player waiting on their opponent can claim
+        if claimant == game.current_player {
+            return Err(Error::CannotClaimTimeoutOnOwnTurn);
+        }
+
+        let deadline = game.last_move_timestamp.saturating_add(game.move_timeout);
+        if env.ledger().timestamp() <= deadline {
+            return Err(Error::MoveDeadlineNotReached);
+        }
+
+        game.status = Self::win_status(&claimant);
+
+        Self::save_game(&env, game_id, &game);
+
+        events::game_finished(&env, game_id, &game.status);
+        Ok(game)
     }
 
-    pub fn get_board(env: Env, game_id: u32) -> Vec<Option<Player>> {
-        let game: Game = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Game(game_id))
-            .expect("Game not found");
-        game.board
-    }
-
-    fn check_winner(board: &Vec<Option<Player>>) -> GameStatus {
-        let winning_positions = [
-            [0, 1, 2],
-            [3, 4, 5],
-            [6, 7, 8], // rows
-            [0, 3, 6],
-            [1, 4, 7],
-            [2, 5, 8], // columns
-            [0, 4, 8],
-            [2, 4, 6], // diagonals
-        ];
-
-        for positions in winning_positions.iter() {
-            let a = board.get(positions[0]).unwrap();
-            let b = board.get(positions[1]).unwrap();
-            let c = board.get(positions[2]).unwrap();
-
-            if let (Some(player_a), Some(player_b), Some(player_c)) = (a, b, c) {
-                if player_a == player_b && player_b == player_c {
-                    return match player_a {
-                        Player::X => GameStatus::XWins,
-                        Player::O => GameStatus::OWins,
-                    };
+    /// Resign the game, awarding the win to the opponent
+    pub fn resign(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        let side = Self::active_side(&game, &player)?;
+
+        game.status = Self::win_status(&Self::opponent(&side));
+        game.draw_offer = None;
+
+        Self::save_game(&env, game_id, &game);
+
+        events::game_finished(&env, game_id, &game.status);
+        Ok(game)
+    }
+
+    /// Offer the opponent a draw; the offer stands until answered or a move is made
+    pub fn offer_draw(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        Self::active_side(&game, &player)?;
+
+        if game.draw_offer.is_some() {
+            return Err(Error::InvalidOffer);
+        }
+
+        game.draw_offer = Some(player.clone());
+
+        Self::save_game(&env, game_id, &game);
+
+        events::draw_offered(&env, game_id, &player);
+        Ok(game)
+    }
+
+    /// Accept the opponent's pending draw offer, ending the game as a draw
+    pub fn accept_draw(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        Self::answer_draw_offer(&mut game, &player)?;
+
+        game.status = GameStatus::Draw;
+
+        Self::save_game(&env, game_id, &game);
+
+        events::game_finished(&env, game_id, &game.status);
+        Ok(game)
+    }
+
+    /// Decline the opponent's pending draw offer
+    pub fn decline_draw(env: Env, game_id: u32, player: Address) -> Result<Game, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.moves)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        Self::answer_draw_offer(&mut game, &player)?;
+
+        Self::save_game(&env, game_id, &game);
+
+        events::draw_declined(&env, game_id, &player);
+        Ok(game)
+    }
+
+    /// Offer the opponent a rematch of a finished game, replacing any earlier offer
+    pub fn offer_rematch(env: Env, game_id: u32, player: Address, options: RematchOptions) -> Result<(), Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.new_games)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        Self::require_rematchable(&game, &player)?;
+
+        let key = DataKey::RematchOffer(game_id);
+        let offer = RematchOffer { offered_by: player.clone(), options };
+        env.storage().persistent().set(&key, &offer);
+        Self::extend_persistent(&env, &key, SETTLED_GAME_TTL);
+
+        events::rematch_offered(&env, game_id, &player);
+        Ok(())
+    }
+
+    /// Withdraw your own pending rematch offer before the opponent accepts it
+    pub fn cancel_rematch_offer(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        player.require_auth();
+
+        let key = DataKey::RematchOffer(game_id);
+        let offer: RematchOffer = env.storage().persistent().get(&key).ok_or(Error::NoRematchOffer)?;
+        if offer.offered_by != player {
+            return Err(Error::NoRematchOffer);
+        }
+        env.storage().persistent().remove(&key);
+
+        events::rematch_cancelled(&env, game_id, &player);
+        Ok(())
+    }
+
+    /// Accept the opponent's rematch offer, starting a new game between the
+    /// same players with the same bet. Returns the new game ID.
+    pub fn accept_rematch(env: Env, game_id: u32, player: Address) -> Result<u32, Error> {
+        player.require_auth();
+        Self::require_unpaused(&env, |paused| paused.new_games)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
+        Self::require_rematchable(&game, &player)?;
+
+        let key = DataKey::RematchOffer(game_id);
+        let offer: RematchOffer = env.storage().persistent().get(&key).ok_or(Error::NoRematchOffer)?;
+        if offer.offered_by == player {
+            return Err(Error::InvalidOffer);
+        }
+
+        let old_x = game.player_x.clone();
+        let old_o = game.player_o.clone().unwrap();
+        let (player_x, player_o) = if offer.options.keep_sides {
+            (old_x.clone(), old_o.clone())
+        } else {
+            (old_o.clone(), old_x.clone())
+        };
+
+        let bet = if game.has_bet {
+            let game_bet = Self::load_game_bet(&env, game_id)?;
+            if offer.options.roll_over {
+                Self::require_unpaused(&env, |paused| paused.claims)?;
+                for claimant in [&old_x, &old_o] {
+                    match Self::claim_internal(&env, game_id, &game, claimant) {
+                        Ok(()) | Err(Error::PlayerCannotClaim) | Err(Error::RewardsAlreadyClaimed) => {}
+                        Err(err) => return Err(err),
+                    }
                 }
             }
-        }
+            Self::require_bet_balance(&env, &player_x, game_bet.amount, &game_bet.token_type)?;
+            Self::require_bet_balance(&env, &player_o, game_bet.amount, &game_bet.token_type)?;
+            Some((game_bet.amount, game_bet.token_type))
+        } else {
+            None
+        };
 
-        let mut is_full = true;
-        for i in 0..9 {
-            if board.get(i).unwrap().is_none() {
-                is_full = false;
-                break;
+        // Same terms as the finished game, with the default first move
+        let options = GameOptions {
+            move_timeout: Some(game.move_timeout),
+            join_code_hash: game.join_code_hash.clone(),
+            size: Some(game.size),
+            win_length: Some(game.win_length),
+            mode: game.mode.clone(),
+            misere: game.misere,
+            shape: game.shape.clone(),
+            ..Default::default()
+        };
+        let new_game_id = Self::create_game_internal(
+            &env,
+            player_x.clone(),
+            &options,
+            bet.as_ref().map(|(amount, _)| *amount),
+            bet.as_ref().map(|(_, token_type)| token_type.clone()),
+        )?;
+
+        if let Some((amount, token_type)) = bet {
+            for staker in [&player_x, &player_o] {
+                Self::lock_bet(&env, staker, amount, &token_type);
+                events::bet_locked(&env, new_game_id, staker, amount, &token_type);
             }
+            let game_bet = GameBet {
+                amount,
+                token_type,
+                player_x_paid: true,
+                player_o_paid: true,
+                rewards_claimed: false,
+                player_x_claimed: false,
+                player_o_claimed: false,
+                fees: Self::fee_config(&env),
+            };
+            Self::save_game_bet(&env, new_game_id, &game_bet);
         }
 
-        if is_full {
-            GameStatus::Draw
+        let mut rematch = Self::load_game(&env, new_game_id)?;
+        Self::index_player_game(&env, &player_o, new_game_id);
+        events::player_joined(&env, new_game_id, &player_o);
+        rematch.player_o = Some(player_o);
+        rematch.previous_game_id = Some(game_id);
+        Self::save_game(&env, new_game_id, &rematch);
+
+        game.rematch_game_id = Some(new_game_id);
+        Self::save_game(&env, game_id, &game);
+        env.storage().persistent().remove(&key);
+
+        events::rematch_accepted(&env, game_id, &player, new_game_id);
+        Ok(new_game_id)
+    }
+
+    pub fn get_game(env: Env, game_id: u32) -> Result<Game, Error> {
+        Self::load_game(&env, game_id)
+    }
+
+    /// The board as one cell per position, expanded from the stored bitmasks
+    pub fn get_board(env: Env, game_id: u32) -> Result<Vec<Option<Player>>, Error> {
+        let game = Self::load_game(&env, game_id)?;
+        let board = Board::load(&game.shape, game.size, &game.board_x, &game.board_o);
+
+        let mut cells = Vec::new(&env);
+        for position in 0..board.cells() {
+            cells.push_back(board.get(position));
+        }
+        Ok(cells)
+    }
+
+    /// Extend the storage TTL of a game and its bet so they are not archived.
+    /// Anyone may pay for this; games are also extended whenever they are used.
+    pub fn bump_game(env: Env, game_id: u32) -> Result<(), Error> {
+        Self::load_game(&env, game_id)?;
+        Self::bump_instance(&env);
+        Ok(())
+    }
+
+    /// Moves played in a game, oldest first. Games started before move
+    /// history was kept only list the moves made since the upgrade.
+    pub fn get_moves(env: Env, game_id: u32) -> Result<Vec<Move>, Error> {
+        Self::load_game(&env, game_id)?;
+        Ok(env
+            .storage()
+            .persistent()
+            .get(&DataKey::GameMoves(game_id))
+            .unwrap_or(Vec::new(&env)))
+    }
+
+    /// Sub-board progress of an Ultimate game; None for classic games
+    pub fn get_ultimate_state(env: Env, game_id: u32) -> Result<Option<UltimateState>, Error> {
+        Self::load_game(&env, game_id)?;
+        Ok(env.storage().persistent().get(&DataKey::UltimateState(game_id)))
+    }
+
+    fn record_move(env: &Env, game_id: u32, player: &Address, position: u32, symbol: &Player) {
+        let key = DataKey::GameMoves(game_id);
+        let mut moves: Vec<Move> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+        moves.push_back(Move {
+            player: player.clone(),
+            position,
+            symbol: symbol.clone(),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&key, &moves);
+    }
+
+    /// Status of the board after `mover` places `symbol` on `position`, with
+    /// the completed line if there is one. Only lines through that move can be
+    /// new. A line counts for the mover whichever mark it is made of, which
+    /// only matters in Wild games; under misère rules it counts against them.
+    fn check_winner(
+        env: &Env,
+        board: &Board,
+        position: u32,
+        symbol: &Player,
+        mover: &Player,
+        win_length: u32,
+        misere: bool,
+    ) -> (GameStatus, Option<Vec<u32>>) {
+        if let Some(line) = board.line_through(env, position, symbol, win_length) {
+            let winner = if misere { Self::opponent(mover) } else { mover.clone() };
+            return (Self::win_status(&winner), Some(line));
+        }
+
+        if board.is_full() {
+            (GameStatus::Draw, None)
         } else {
-            GameStatus::InProgress
+            (GameStatus::InProgress, None)
+        }
+    }
+
+    /// Settle an Ultimate move already placed on `board`: check it went to
+    /// the sub-board it was sent to, then score that sub-board and the grid of
+    /// sub-boards. The winning line of an Ultimate game lists sub-boards.
+    /// Sub-boards always go to the player completing them; misère rules only
+    /// apply to the line of sub-boards.
+    fn play_ultimate(
+        env: &Env,
+        game_id: u32,
+        board: &Board,
+        position: u32,
+        player: &Player,
+        misere: bool,
+    ) -> Result<(GameStatus, Option<Vec<u32>>), Error> {
+        let key = DataKey::UltimateState(game_id);
+        let mut state: UltimateState = env.storage().persistent().get(&key).ok_or(Error::GameNotFound)?;
+
+        // Moves must go to the sub-board the opponent sent them to, and never
+        // to one that is already won or full
+        let (sub_board, cell) = board.block_of(SUB_BOARD_SIZE, position);
+        if state.closed & (1 << sub_board) != 0 || state.next_board.is_some_and(|next_board| next_board != sub_board) {
+            return Err(Error::IllegalMove);
         }
+
+        let sub_board_cells = board.block(SUB_BOARD_SIZE, sub_board);
+        let (sub_status, _) = Self::check_winner(env, &sub_board_cells, cell, player, player, SUB_BOARD_SIZE, false);
+        let (mut status, mut line) = (GameStatus::InProgress, None);
+        if sub_status != GameStatus::InProgress {
+            state.closed |= 1 << sub_board;
+            events::sub_board_closed(env, game_id, sub_board, &sub_status);
+        }
+        if sub_status == Self::win_status(player) {
+            let mut won = Board::load(&BoardShape::Square, SUB_BOARD_SIZE, &state.won_x, &state.won_o);
+            won.set(sub_board, player);
+            (state.won_x, state.won_o) = won.to_bytes(env);
+            (status, line) = Self::check_winner(env, &won, sub_board, player, player, SUB_BOARD_SIZE, misere);
+        }
+
+        // Drawn sub-boards count for nobody, so a game without a line ends
+        // drawn once every sub-board is closed
+        let all_closed = (1 << (SUB_BOARD_SIZE * SUB_BOARD_SIZE)) - 1;
+        if status == GameStatus::InProgress && state.closed == all_closed {
+            status = GameStatus::Draw;
+        }
+
+        // The cell played sends the opponent to the matching sub-board,
+        // or lets them play anywhere open if that one is closed
+        state.next_board = (state.closed & (1 << cell) == 0).then_some(cell);
+        env.storage().persistent().set(&key, &state);
+        Ok((status, line))
     }
 }
 
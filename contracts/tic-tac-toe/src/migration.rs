@@ -0,0 +1,149 @@
+//! Storage layout migrations.
+//!
+//! `SCHEMA_VERSION` is bumped whenever a stored type changes shape. The layout
+//! each older version wrote is kept here so `migrate` can decode those entries
+//! and rewrite them in the next version's layout:
+//!
+//! | Version | Change                                                         |
+//! |---------|----------------------------------------------------------------|
+//! | 1       | First versioned layout: one cell per position, no game options |
+//! | 2       | Bitset boards, board and game options, rematches, bet fees     |
+//!
+//! Each step decodes the entries of one version and writes the next, so a
+//! step only uses the current types for layouts that have not changed since.
+use soroban_sdk::{contracttype, Address, Bytes, Env, Vec};
+
+use crate::{
+    BoardShape, DataKey, FeeConfig, Game, GameBet, GameMode, GameStatus, Player, SideChoice,
+    TicTacToeContract, TokenType,
+};
+
+/// `Game` as stored by schema version 1
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype(export = false)]
+pub struct GameV1 {
+    pub board: Vec<Option<Player>>,
+    pub current_player: Player,
+    pub player_x: Address,
+    pub player_o: Option<Address>,
+    pub status: GameStatus,
+    pub has_bet: bool,
+    pub move_timeout: u64,
+    pub last_move_timestamp: u64,
+    pub last_move_ledger: u32,
+    pub draw_offer: Option<Address>,
+}
+
+/// `GameBet` as stored by schema version 1
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype(export = false)]
+pub struct GameBetV1 {
+    pub amount: i128,
+    pub token_type: TokenType,
+    pub player_x_paid: bool,
+    pub player_o_paid: bool,
+    pub rewards_claimed: bool,
+    pub player_x_claimed: bool,
+    pub player_o_claimed: bool,
+}
+
+/// Rewrite the entries of one game from `from_version` into the next layout
+pub(crate) fn migrate_game(env: &Env, from_version: u32, game_id: u32) {
+    match from_version {
+        1 => v1_to_v2(env, game_id),
+        _ => panic!("no migration from schema version {}", from_version),
+    }
+}
+
+fn v1_to_v2(env: &Env, game_id: u32) {
+    let storage = env.storage().persistent();
+
+    // Bets placed before fees existed stay fee-free
+    if let Some(old) = storage.get::<DataKey, GameBetV1>(&DataKey::GameBet(game_id)) {
+        let game_bet = GameBet {
+            amount: old.amount,
+            token_type: old.token_type,
+            player_x_paid: old.player_x_paid,
+            player_o_paid: old.player_o_paid,
+            rewards_claimed: old.rewards_claimed,
+            player_x_claimed: old.player_x_claimed,
+            player_o_claimed: old.player_o_claimed,
+            fees: FeeConfig::default(),
+        };
+        TicTacToeContract::save_game_bet(env, game_id, &game_bet);
+    }
+
+    // Schema 1 games are all public classic 3x3 games that seated the creator
+    // as X, moving first. Their moves were never recorded, but the winning
+    // line is still on the board, and the nine bits fit in two bytes.
+    if let Some(old) = storage.get::<DataKey, GameV1>(&DataKey::Game(game_id)) {
+        let (board_x, board_o) = board_masks(&old.board);
+        let line = match old.status {
+            GameStatus::XWins => classic_winning_line(board_x),
+            GameStatus::OWins => classic_winning_line(board_o),
+            _ => None,
+        };
+        let winning_line = match line {
+            Some(line) => Vec::from_array(env, line),
+            None => Vec::new(env),
+        };
+        let game = Game {
+            mode: GameMode::Classic,
+            misere: false,
+            shape: BoardShape::Square,
+            size: 3,
+            win_length: 3,
+            board_x: Bytes::from_array(env, &(board_x as u16).to_le_bytes()),
+            board_o: Bytes::from_array(env, &(board_o as u16).to_le_bytes()),
+            current_player: old.current_player,
+            player_x: old.player_x,
+            player_o: old.player_o,
+            status: old.status,
+            has_bet: old.has_bet,
+            move_timeout: old.move_timeout,
+            last_move_timestamp: old.last_move_timestamp,
+            last_move_ledger: old.last_move_ledger,
+            draw_offer: old.draw_offer,
+            invited: None,
+            join_code_hash: None,
+            creator_side: SideChoice::X,
+            first_move: SideChoice::X,
+            previous_game_id: None,
+            rematch_game_id: None,
+            winning_line,
+        };
+        TicTacToeContract::save_game(env, game_id, &game);
+    }
+}
+
+/// Positions of a completed line on a 3x3 bitmask
+fn classic_winning_line(player_board: u32) -> Option<[u32; 3]> {
+    const LINES: [[u32; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8], // rows
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8], // columns
+        [0, 4, 8],
+        [2, 4, 6], // diagonals
+    ];
+
+    LINES.into_iter().find(|line| {
+        line.iter()
+            .all(|position| player_board & (1 << position) != 0)
+    })
+}
+
+/// Bitmasks of the positions X and O hold on a board stored cell by cell
+fn board_masks(board: &Vec<Option<Player>>) -> (u32, u32) {
+    let (mut board_x, mut board_o) = (0, 0);
+    for (position, cell) in board.iter().enumerate() {
+        match cell {
+            Some(Player::X) => board_x |= 1 << position,
+            Some(Player::O) => board_o |= 1 << position,
+            None => {}
+        }
+    }
+    (board_x, board_o)
+}
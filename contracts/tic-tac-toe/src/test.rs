@@ -1,112 +1,138 @@
 use super::*;
-use soroban_sdk::{symbol_short, Env};
+extern crate std;
+use soroban_sdk::{
+    symbol_short,
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, AuthorizedFunction, AuthorizedInvocation, EnvTestConfig, Events, Ledger, MockAuth,
+        MockAuthInvoke,
+    },
+    token, vec, Address, Bytes, BytesN, Env, IntoVal,
+};
+
+fn create_token_contract<'a>(env: &Env) -> (Address, token::StellarAssetClient<'a>) {
+    let admin = Address::generate(env);
+    let sac = env.register_stellar_asset_contract_v2(admin);
+    let address = sac.address();
+    (address.clone(), token::StellarAssetClient::new(env, &address))
+}
+
+fn setup_contract<'a>(env: &Env) -> (TicTacToeContractClient<'a>, token::StellarAssetClient<'a>) {
+    let (native_token, native) = create_token_contract(env);
+    let admin = Address::generate(env);
+    let contract_id = env.register(TicTacToeContract, (admin, native_token));
+    (TicTacToeContractClient::new(env, &contract_id), native)
+}
 
 #[test]
 fn test_create_game() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     assert_eq!(game_id, 1);
 
     let game = client.join_game(&game_id, &player_o);
     assert_eq!(game.player_x, player_x);
-    assert_eq!(game.player_o, player_o);
+    assert_eq!(game.player_o, Some(player_o.clone()));
     assert_eq!(game.current_player, Player::X);
     assert_eq!(game.status, GameStatus::InProgress);
-    assert_eq!(game.board.len(), 9);
+    assert_eq!((game.size, game.win_length), (3, 3));
+    assert_eq!(game.board_x, Bytes::from_array(&env, &[0, 0]));
+    assert_eq!(game.board_o, Bytes::from_array(&env, &[0, 0]));
 
+    let board = client.get_board(&game_id);
+    assert_eq!(board.len(), 9);
     for i in 0..9 {
-        assert_eq!(game.board.get(i).unwrap(), None);
+        assert_eq!(board.get(i).unwrap(), None);
     }
 }
 
 #[test]
 fn test_make_move() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     let game = client.make_move(&game_id, &player_x, &0);
-    assert_eq!(game.board.get(0).unwrap(), Some(Player::X));
+    assert_eq!(game.board_x, Bytes::from_array(&env, &[0b0000_0001, 0]));
+    assert_eq!(client.get_board(&game_id).get(0).unwrap(), Some(Player::X));
     assert_eq!(game.current_player, Player::O);
     assert_eq!(game.status, GameStatus::InProgress);
 
     let game = client.make_move(&game_id, &player_o, &4);
-    assert_eq!(game.board.get(4).unwrap(), Some(Player::O));
+    assert_eq!(game.board_o, Bytes::from_array(&env, &[0b0001_0000, 0]));
+    assert_eq!(client.get_board(&game_id).get(4).unwrap(), Some(Player::O));
     assert_eq!(game.current_player, Player::X);
     assert_eq!(game.status, GameStatus::InProgress);
 }
 
 #[test]
-#[should_panic(expected = "Not your turn")]
 fn test_wrong_player_move() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
-    client.make_move(&game_id, &player_o, &0);
+    assert_eq!(client.try_make_move(&game_id, &player_o, &0), Err(Ok(Error::NotYourTurn)));
 }
 
 #[test]
-#[should_panic(expected = "Position already taken")]
 fn test_position_already_taken() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
-    client.make_move(&game_id, &player_o, &0);
+    assert_eq!(client.try_make_move(&game_id, &player_o, &0), Err(Ok(Error::PositionAlreadyTaken)));
 }
 
 #[test]
-#[should_panic(expected = "Invalid position: must be 0-8")]
 fn test_invalid_position() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
-    client.make_move(&game_id, &player_x, &9);
+    assert_eq!(client.try_make_move(&game_id, &player_x, &9), Err(Ok(Error::InvalidPosition)));
 }
 
 #[test]
 fn test_winning_game_x() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
@@ -121,13 +147,13 @@ fn test_winning_game_x() {
 #[test]
 fn test_winning_game_o() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
@@ -143,13 +169,13 @@ fn test_winning_game_o() {
 #[test]
 fn test_draw_game() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
@@ -166,16 +192,15 @@ fn test_draw_game() {
 }
 
 #[test]
-#[should_panic(expected = "Game is already finished")]
 fn test_move_after_game_finished() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
@@ -184,22 +209,22 @@ fn test_move_after_game_finished() {
     client.make_move(&game_id, &player_o, &4);
     client.make_move(&game_id, &player_x, &2);
 
-    client.make_move(&game_id, &player_o, &5);
+    assert_eq!(client.try_make_move(&game_id, &player_o, &5), Err(Ok(Error::GameAlreadyFinished)));
 }
 
 #[test]
 fn test_multiple_games() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x1 = symbol_short!("alice");
-    let player_o1 = symbol_short!("bob");
-    let player_x2 = symbol_short!("charlie");
-    let player_o2 = symbol_short!("david");
+    let player_x1 = Address::generate(&env);
+    let player_o1 = Address::generate(&env);
+    let player_x2 = Address::generate(&env);
+    let player_o2 = Address::generate(&env);
 
-    let game_id1 = client.create_game(&player_x1);
-    let game_id2 = client.create_game(&player_x2);
+    let game_id1 = client.create_game(&player_x1, &GameOptions::default());
+    let game_id2 = client.create_game(&player_x2, &GameOptions::default());
 
     assert_eq!(game_id1, 1);
     assert_eq!(game_id2, 2);
@@ -210,25 +235,25 @@ fn test_multiple_games() {
     client.make_move(&game_id1, &player_x1, &0);
     client.make_move(&game_id2, &player_x2, &4);
 
-    let game1 = client.get_game(&game_id1);
-    let game2 = client.get_game(&game_id2);
+    let board1 = client.get_board(&game_id1);
+    let board2 = client.get_board(&game_id2);
 
-    assert_eq!(game1.board.get(0).unwrap(), Some(Player::X));
-    assert_eq!(game1.board.get(4).unwrap(), None);
-    assert_eq!(game2.board.get(0).unwrap(), None);
-    assert_eq!(game2.board.get(4).unwrap(), Some(Player::X));
+    assert_eq!(board1.get(0).unwrap(), Some(Player::X));
+    assert_eq!(board1.get(4).unwrap(), None);
+    assert_eq!(board2.get(0).unwrap(), None);
+    assert_eq!(board2.get(4).unwrap(), Some(Player::X));
 }
 
 #[test]
 fn test_get_board() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
 
     client.make_move(&game_id, &player_x, &0);
@@ -244,59 +269,76 @@ fn test_get_board() {
 }
 
 #[test]
-#[should_panic(expected = "Game needs a second player")]
 fn test_move_without_second_player() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
+    let player_x = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
-    client.make_move(&game_id, &player_x, &0);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(
+        client.try_make_move(&game_id, &player_x, &0),
+        Err(Ok(Error::GameNeedsSecondPlayer))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Game already has two players")]
 fn test_join_game_twice() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
-    let player_o2 = symbol_short!("charlie");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let player_o2 = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
-    client.join_game(&game_id, &player_o2);
+    assert_eq!(
+        client.try_join_game(&game_id, &player_o2),
+        Err(Ok(Error::GameAlreadyHasTwoPlayers))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Cannot join your own game")]
 fn test_join_own_game() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
+    let player_x = Address::generate(&env);
 
-    let game_id = client.create_game(&player_x);
-    client.join_game(&game_id, &player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_join_game(&game_id, &player_x), Err(Ok(Error::CannotJoinOwnGame)));
+}
+
+#[test]
+fn test_game_not_found() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player = Address::generate(&env);
+
+    assert_eq!(client.try_get_game(&42), Err(Ok(Error::GameNotFound)));
+    assert_eq!(client.try_get_board(&42), Err(Ok(Error::GameNotFound)));
+    assert_eq!(client.try_join_game(&42, &player), Err(Ok(Error::GameNotFound)));
+    assert_eq!(client.try_make_move(&42, &player, &0), Err(Ok(Error::GameNotFound)));
 }
 
 #[test]
 fn test_list_games() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x1 = symbol_short!("alice");
-    let player_o1 = symbol_short!("bob");
-    let player_x2 = symbol_short!("charlie");
+    let player_x1 = Address::generate(&env);
+    let player_o1 = Address::generate(&env);
+    let player_x2 = Address::generate(&env);
 
-    let game_id1 = client.create_game(&player_x1);
-    let _game_id2 = client.create_game(&player_x2);
+    let game_id1 = client.create_game(&player_x1, &GameOptions::default());
+    let _game_id2 = client.create_game(&player_x2, &GameOptions::default());
     client.join_game(&game_id1, &player_o1);
 
     let games = client.list_games();
@@ -305,13 +347,13 @@ fn test_list_games() {
     let game1_info = games.get(0).unwrap();
     assert_eq!(game1_info.id, 1);
     assert_eq!(game1_info.player_x, player_x1);
-    assert_eq!(game1_info.player_o, player_o1);
+    assert_eq!(game1_info.player_o, Some(player_o1.clone()));
     assert_eq!(game1_info.status, GameStatus::InProgress);
 
     let game2_info = games.get(1).unwrap();
     assert_eq!(game2_info.id, 2);
     assert_eq!(game2_info.player_x, player_x2);
-    assert_eq!(game2_info.player_o, symbol_short!("waiting"));
+    assert_eq!(game2_info.player_o, None);
     assert_eq!(game2_info.status, GameStatus::InProgress);
 }
 
@@ -320,10 +362,10 @@ fn test_list_games() {
 #[test]
 fn test_deposit_withdraw_native() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
+    let user = Address::generate(&env);
     
     // Initial balance should be empty
     let balance = client.get_balance(&user);
@@ -331,11 +373,13 @@ fn test_deposit_withdraw_native() {
     assert_eq!(balance.tokens.len(), 0);
 
     // Deposit native XLM
+    native.mint(&user, &1000);
     client.deposit_native(&user, &1000);
     let balance = client.get_balance(&user);
     assert_eq!(balance.native, 1000);
 
     // Deposit more
+    native.mint(&user, &500);
     client.deposit_native(&user, &500);
     let balance = client.get_balance(&user);
     assert_eq!(balance.native, 1500);
@@ -344,41 +388,46 @@ fn test_deposit_withdraw_native() {
     client.withdraw_native(&user, &300);
     let balance = client.get_balance(&user);
     assert_eq!(balance.native, 1200);
+
+    // Funds actually moved between the user and the contract
+    let native_token = token::Client::new(&env, &client.get_native_token());
+    assert_eq!(native_token.balance(&user), 300);
+    assert_eq!(native_token.balance(&client.address), 1200);
 }
 
 #[test]
-#[should_panic(expected = "Amount must be positive")]
 fn test_deposit_zero_native() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
-    client.deposit_native(&user, &0);
+    let user = Address::generate(&env);
+    assert_eq!(client.try_deposit_native(&user, &0), Err(Ok(Error::AmountMustBePositive)));
 }
 
 #[test]
-#[should_panic(expected = "Insufficient balance")]
 fn test_withdraw_insufficient_native() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
+    let user = Address::generate(&env);
+    native.mint(&user, &500);
     client.deposit_native(&user, &500);
-    client.withdraw_native(&user, &600);
+    assert_eq!(client.try_withdraw_native(&user, &600), Err(Ok(Error::InsufficientBalance)));
 }
 
 #[test]
 fn test_deposit_withdraw_tokens() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
-    let token_addr = symbol_short!("USDC");
+    let user = Address::generate(&env);
+    let (token_addr, usdc) = create_token_contract(&env);
     
     // Deposit tokens
+    usdc.mint(&user, &2000);
     client.deposit_token(&user, &token_addr, &2000);
     let balance = client.get_balance(&user);
     assert_eq!(balance.tokens.len(), 1);
@@ -387,6 +436,7 @@ fn test_deposit_withdraw_tokens() {
     assert_eq!(amount, 2000);
 
     // Deposit more of the same token
+    usdc.mint(&user, &1000);
     client.deposit_token(&user, &token_addr, &1000);
     let balance = client.get_balance(&user);
     assert_eq!(balance.tokens.len(), 1);
@@ -395,7 +445,8 @@ fn test_deposit_withdraw_tokens() {
     assert_eq!(amount, 3000);
 
     // Deposit different token
-    let token_addr2 = symbol_short!("USDT");
+    let (token_addr2, usdt) = create_token_contract(&env);
+    usdt.mint(&user, &500);
     client.deposit_token(&user, &token_addr2, &500);
     let balance = client.get_balance(&user);
     assert_eq!(balance.tokens.len(), 2);
@@ -409,47 +460,51 @@ fn test_deposit_withdraw_tokens() {
 }
 
 #[test]
-#[should_panic(expected = "Insufficient token balance")]
 fn test_withdraw_insufficient_tokens() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
-    let token_addr = symbol_short!("USDC");
+    let user = Address::generate(&env);
+    let (token_addr, usdc) = create_token_contract(&env);
     
+    usdc.mint(&user, &1000);
     client.deposit_token(&user, &token_addr, &1000);
-    client.withdraw_token(&user, &token_addr, &1500);
+    assert_eq!(
+        client.try_withdraw_token(&user, &token_addr, &1500),
+        Err(Ok(Error::InsufficientTokenBalance))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Token not found in balance")]
 fn test_withdraw_nonexistent_token() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let user = symbol_short!("alice");
-    let token_addr = symbol_short!("USDC");
+    let user = Address::generate(&env);
+    let (token_addr, _) = create_token_contract(&env);
     
-    client.withdraw_token(&user, &token_addr, &100);
+    assert_eq!(client.try_withdraw_token(&user, &token_addr, &100), Err(Ok(Error::TokenNotFound)));
 }
 
 #[test]
 fn test_create_game_with_native_bet() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Fund players
+    native.mint(&player_x, &2000);
     client.deposit_native(&player_x, &2000);
+    native.mint(&player_o, &2000);
     client.deposit_native(&player_o, &2000);
     
     // Create game with bet
-    let game_id = client.create_game_with_bet(&player_x, &500, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &500, &TokenType::Native, &GameOptions::default());
     assert_eq!(game_id, 1);
     
     // Check player X's balance is locked
@@ -474,17 +529,18 @@ fn test_create_game_with_native_bet() {
 #[test]
 fn test_create_game_with_token_bet() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let token_addr = symbol_short!("USDC");
+    let player_x = Address::generate(&env);
+    let (token_addr, usdc) = create_token_contract(&env);
     
     // Fund player
+    usdc.mint(&player_x, &2000);
     client.deposit_token(&player_x, &token_addr, &2000);
     
     // Create game with token bet
-    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Stellar(token_addr.clone()));
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Stellar(token_addr.clone()), &GameOptions::default());
     
     // Check player's token balance is locked
     let balance = client.get_balance(&player_x);
@@ -499,43 +555,50 @@ fn test_create_game_with_token_bet() {
 }
 
 #[test]
-#[should_panic(expected = "Bet amount must be positive")]
 fn test_create_game_zero_bet() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    client.create_game_with_bet(&player_x, &0, &TokenType::Native);
+    let player_x = Address::generate(&env);
+    assert_eq!(
+        client.try_create_game_with_bet(&player_x, &0, &TokenType::Native, &GameOptions::default()),
+        Err(Ok(Error::BetAmountMustBePositive))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Insufficient native balance for bet")]
 fn test_create_game_insufficient_balance() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
+    let player_x = Address::generate(&env);
+    native.mint(&player_x, &200);
     client.deposit_native(&player_x, &200);
-    client.create_game_with_bet(&player_x, &500, &TokenType::Native);
+    assert_eq!(
+        client.try_create_game_with_bet(&player_x, &500, &TokenType::Native, &GameOptions::default()),
+        Err(Ok(Error::InsufficientNativeBalanceForBet))
+    );
 }
 
 #[test]
 fn test_join_betting_game() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Fund players
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
     // Create and join betting game
-    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &GameOptions::default());
     let game = client.join_game(&game_id, &player_o);
     
     // Check both players' balances are locked
@@ -552,42 +615,48 @@ fn test_join_betting_game() {
     // Check game structure
     assert_eq!(game.has_bet, true);
     assert_eq!(game.player_x, player_x);
-    assert_eq!(game.player_o, player_o);
+    assert_eq!(game.player_o, Some(player_o.clone()));
 }
 
 #[test]
-#[should_panic(expected = "Insufficient native balance for bet")]
 fn test_join_betting_game_insufficient_balance() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Fund only player X adequately
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &200);
     client.deposit_native(&player_o, &200);
     
-    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native);
-    client.join_game(&game_id, &player_o);
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &GameOptions::default());
+    assert_eq!(
+        client.try_join_game(&game_id, &player_o),
+        Err(Ok(Error::InsufficientNativeBalanceForBet))
+    );
 }
 
 #[test]
 fn test_winner_claims_all_rewards() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Fund players
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
     // Create and join betting game
-    let game_id = client.create_game_with_bet(&player_x, &400, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &400, &TokenType::Native, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play game to X victory
@@ -616,19 +685,23 @@ fn test_winner_claims_all_rewards() {
 #[test]
 fn test_draw_both_players_claim() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Fund players
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
     // Create and join betting game
-    let game_id = client.create_game_with_bet(&player_x, &250, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &250, &TokenType::Native, &GameOptions::default());
+    assert!(!client.get_game_bet(&game_id).unwrap().player_o_paid);
     client.join_game(&game_id, &player_o);
+    assert!(client.get_game_bet(&game_id).unwrap().player_o_paid);
     
     // Play to draw
     client.make_move(&game_id, &player_x, &0);
@@ -646,7 +719,9 @@ fn test_draw_both_players_claim() {
     
     // Both players claim their bets back
     client.claim_rewards(&game_id, &player_x);
+    assert!(!client.get_game_bet(&game_id).unwrap().rewards_claimed);
     client.claim_rewards(&game_id, &player_o);
+    assert!(client.get_game_bet(&game_id).unwrap().rewards_claimed);
     
     let balance_x = client.get_balance(&player_x);
     let balance_o = client.get_balance(&player_o);
@@ -655,17 +730,16 @@ fn test_draw_both_players_claim() {
 }
 
 #[test]
-#[should_panic(expected = "Game has no betting")]
 fn test_claim_rewards_no_betting() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
     // Create regular game without betting
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play to completion
@@ -675,45 +749,47 @@ fn test_claim_rewards_no_betting() {
     client.make_move(&game_id, &player_o, &4);
     client.make_move(&game_id, &player_x, &2);
     
-    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::GameHasNoBetting)));
 }
 
 #[test]
-#[should_panic(expected = "Game is still in progress")]
 fn test_claim_rewards_game_in_progress() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
-    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Make one move but don't finish
     client.make_move(&game_id, &player_x, &0);
     
-    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::GameStillInProgress)));
 }
 
 #[test]
-#[should_panic(expected = "Player cannot claim rewards")]
 fn test_loser_cannot_claim() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
-    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play to X victory
@@ -724,23 +800,24 @@ fn test_loser_cannot_claim() {
     client.make_move(&game_id, &player_x, &2);
     
     // Player O (loser) tries to claim
-    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.try_claim_rewards(&game_id, &player_o), Err(Ok(Error::PlayerCannotClaim)));
 }
 
 #[test]
-#[should_panic(expected = "Rewards already claimed")]
 fn test_double_claim_rewards() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
     
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
-    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play to X victory
@@ -754,25 +831,30 @@ fn test_double_claim_rewards() {
     client.claim_rewards(&game_id, &player_x);
     
     // Try to claim again
-    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(
+        client.try_claim_rewards(&game_id, &player_x),
+        Err(Ok(Error::RewardsAlreadyClaimed))
+    );
 }
 
 #[test]
 fn test_token_betting_full_workflow() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
-    let token_addr = symbol_short!("USDC");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let (token_addr, usdc) = create_token_contract(&env);
     
     // Fund players with tokens
+    usdc.mint(&player_x, &5000);
     client.deposit_token(&player_x, &token_addr, &5000);
+    usdc.mint(&player_o, &5000);
     client.deposit_token(&player_o, &token_addr, &5000);
     
     // Create token betting game
-    let game_id = client.create_game_with_bet(&player_x, &1000, &TokenType::Stellar(token_addr.clone()));
+    let game_id = client.create_game_with_bet(&player_x, &1000, &TokenType::Stellar(token_addr.clone()), &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play to O victory
@@ -799,23 +881,27 @@ fn test_token_betting_full_workflow() {
 #[test]
 fn test_mixed_game_types() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_a = symbol_short!("alice");
-    let player_b = symbol_short!("bob");
-    let player_c = symbol_short!("charlie");
-    let player_d = symbol_short!("david");
+    let player_a = Address::generate(&env);
+    let player_b = Address::generate(&env);
+    let player_c = Address::generate(&env);
+    let player_d = Address::generate(&env);
     
     // Fund all players
+    native.mint(&player_a, &1000);
     client.deposit_native(&player_a, &1000);
+    native.mint(&player_b, &1000);
     client.deposit_native(&player_b, &1000);
+    native.mint(&player_c, &1000);
     client.deposit_native(&player_c, &1000);
+    native.mint(&player_d, &1000);
     client.deposit_native(&player_d, &1000);
     
     // Create one regular game and one betting game
-    let regular_game = client.create_game(&player_a);
-    let betting_game = client.create_game_with_bet(&player_c, &300, &TokenType::Native);
+    let regular_game = client.create_game(&player_a, &GameOptions::default());
+    let betting_game = client.create_game_with_bet(&player_c, &300, &TokenType::Native, &GameOptions::default());
     
     // Join games
     client.join_game(&regular_game, &player_b);
@@ -834,20 +920,21 @@ fn test_mixed_game_types() {
 }
 
 #[test]
-#[should_panic(expected = "Not a player in this game")]
 fn test_claim_rewards_wrong_player() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
-    let outsider = symbol_short!("charlie");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let outsider = Address::generate(&env);
     
+    native.mint(&player_x, &1000);
     client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
     client.deposit_native(&player_o, &1000);
     
-    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native);
+    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     
     // Play to X victory
@@ -858,5 +945,3256 @@ fn test_claim_rewards_wrong_player() {
     client.make_move(&game_id, &player_x, &2);
     
     // Outsider tries to claim
-    client.claim_rewards(&game_id, &outsider);
+    assert_eq!(client.try_claim_rewards(&game_id, &outsider), Err(Ok(Error::NotAPlayer)));
+}
+
+#[test]
+fn test_deposit_without_funds_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let user = Address::generate(&env);
+    native.mint(&user, &100);
+
+    assert!(client.try_deposit_native(&user, &500).is_err());
+    assert_eq!(client.get_balance(&user).native, 0);
+    assert_eq!(token::Client::new(&env, &native.address).balance(&user), 100);
+}
+
+#[test]
+fn test_token_custody_reconciles_with_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let (token_addr, usdc) = create_token_contract(&env);
+    let usdc_token = token::Client::new(&env, &token_addr);
+
+    usdc.mint(&player_x, &5000);
+    usdc.mint(&player_o, &5000);
+    client.deposit_token(&player_x, &token_addr, &3000);
+    client.deposit_token(&player_o, &token_addr, &2000);
+    assert_eq!(usdc_token.balance(&client.address), 5000);
+
+    // Locked bets stay in custody until claimed
+    let game_id = client.create_game_with_bet(&player_x, &1000, &TokenType::Stellar(token_addr.clone()), &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    assert_eq!(usdc_token.balance(&client.address), 5000);
+
+    client.make_move(&game_id, &player_x, &0);
+    client.make_move(&game_id, &player_o, &3);
+    client.make_move(&game_id, &player_x, &1);
+    client.make_move(&game_id, &player_o, &4);
+    client.make_move(&game_id, &player_x, &2); // X wins
+    client.claim_rewards(&game_id, &player_x);
+
+    let (_, amount_x) = client.get_balance(&player_x).tokens.get(0).unwrap();
+    let (_, amount_o) = client.get_balance(&player_o).tokens.get(0).unwrap();
+    assert_eq!(amount_x, 4000);
+    assert_eq!(amount_o, 1000);
+    assert_eq!(usdc_token.balance(&client.address), amount_x + amount_o);
+
+    // Withdrawing everything empties the escrow
+    client.withdraw_token(&player_x, &token_addr, &amount_x);
+    client.withdraw_token(&player_o, &token_addr, &amount_o);
+    assert_eq!(usdc_token.balance(&client.address), 0);
+    assert_eq!(usdc_token.balance(&player_x), 6000);
+    assert_eq!(usdc_token.balance(&player_o), 4000);
+}
+
+// === CANCELLATION TESTS ===
+
+#[test]
+fn test_cancel_betting_game_refunds_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    client.deposit_native(&player_x, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &400, &TokenType::Native, &GameOptions::default());
+    assert_eq!(client.get_balance(&player_x).native, 600);
+
+    let game = client.cancel_game(&game_id, &player_x);
+    assert_eq!(game.status, GameStatus::Cancelled);
+    assert_eq!(client.get_balance(&player_x).native, 1000);
+
+    let bet = client.get_game_bet(&game_id).unwrap();
+    assert!(bet.player_x_claimed);
+    assert!(bet.rewards_claimed);
+
+    // The refund cannot be taken twice
+    assert_eq!(client.try_cancel_game(&game_id, &player_x), Err(Ok(Error::GameCancelled)));
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::GameCancelled)));
+    assert_eq!(client.get_balance(&player_x).native, 1000);
+}
+
+#[test]
+fn test_cancelled_game_cannot_be_joined() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    let open_game_id = client.create_game(&player_x, &GameOptions::default());
+    client.cancel_game(&game_id, &player_x);
+
+    assert_eq!(client.try_join_game(&game_id, &player_o), Err(Ok(Error::GameCancelled)));
+
+    // Cancelled games are hidden from listings but still readable by ID
+    let games = client.list_games();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games.get(0).unwrap().id, open_game_id);
+    assert_eq!(client.get_game(&game_id).status, GameStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_game_invalid_requests() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_cancel_game(&game_id, &player_o), Err(Ok(Error::NotGameCreator)));
+
+    client.join_game(&game_id, &player_o);
+    assert_eq!(client.try_cancel_game(&game_id, &player_x), Err(Ok(Error::GameAlreadyStarted)));
+}
+
+// === INVITATION TESTS ===
+
+#[test]
+fn test_invited_game_only_joinable_by_opponent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let options = GameOptions { opponent: Some(player_o.clone()), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    assert_eq!(client.get_game(&game_id).invited, Some(player_o.clone()));
+
+    assert_eq!(client.try_join_game(&game_id, &stranger), Err(Ok(Error::NotInvited)));
+
+    // Invites alone never touch the invitee's index
+    assert_eq!(client.get_player_games(&player_o, &Vec::new(&env), &0, &10).games.len(), 0);
+
+    let game = client.join_game(&game_id, &player_o);
+    assert_eq!(game.player_o, Some(player_o.clone()));
+    assert_eq!(game.status, GameStatus::InProgress);
+
+    let page = client.get_player_games(&player_o, &Vec::new(&env), &0, &10);
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, game_id);
+    assert_eq!(client.get_player_games(&stranger, &Vec::new(&env), &0, &10).games.len(), 0);
+}
+
+#[test]
+fn test_cannot_invite_self() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let options = GameOptions { opponent: Some(player_x.clone()), ..Default::default() };
+    assert_eq!(client.try_create_game(&player_x, &options), Err(Ok(Error::CannotJoinOwnGame)));
+}
+
+#[test]
+fn test_invited_games_are_not_open() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let open_id = client.create_game(&player_x, &GameOptions::default());
+    let invite_id =
+        client.create_game(&player_x, &GameOptions { opponent: Some(player_o.clone()), ..Default::default() });
+
+    let filter = GameFilter { open_only: true, ..GameFilter::any(&env) };
+    let page = client.list_games_page(&0, &10, &filter);
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, open_id);
+
+    let page = client.list_games_page(&0, &10, &GameFilter::any(&env));
+    assert_eq!(page.games.len(), 2);
+    assert_eq!(page.games.get(1).unwrap().invited, Some(player_o));
+    assert_eq!(page.games.get(1).unwrap().id, invite_id);
+}
+
+#[test]
+fn test_decline_invite_refunds_creator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    client.deposit_native(&player_x, &1000);
+
+    let options = GameOptions { opponent: Some(player_o.clone()), ..Default::default() };
+    let game_id = client.create_game_with_bet(&player_x, &400, &TokenType::Native, &options);
+    assert_eq!(client.get_balance(&player_x).native, 600);
+
+    assert_eq!(client.try_decline_invite(&game_id, &player_x), Err(Ok(Error::NotInvited)));
+
+    let game = client.decline_invite(&game_id, &player_o);
+    assert_eq!(game.status, GameStatus::Cancelled);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("declined"), game_id).into_val(&env),
+                player_o.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("cancelled"), game_id).into_val(&env),
+                (player_x.clone(), 400i128).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.get_balance(&player_x).native, 1000);
+    assert!(client.get_game_bet(&game_id).unwrap().rewards_claimed);
+
+    assert_eq!(client.try_decline_invite(&game_id, &player_o), Err(Ok(Error::GameCancelled)));
+    assert_eq!(client.try_join_game(&game_id, &player_o), Err(Ok(Error::GameCancelled)));
+}
+
+#[test]
+fn test_decline_invite_after_join() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let options = GameOptions { opponent: Some(player_o.clone()), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+
+    assert_eq!(client.try_decline_invite(&game_id, &player_o), Err(Ok(Error::GameAlreadyStarted)));
+
+    // Games without an invitation cannot be declined
+    let open_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_decline_invite(&open_id, &player_o), Err(Ok(Error::NotInvited)));
+}
+
+// === PRIVATE GAME TESTS ===
+
+fn join_code(env: &Env, secret: &[u8]) -> (Bytes, BytesN<32>) {
+    let secret = Bytes::from_slice(env, secret);
+    let hash = env.crypto().sha256(&secret).to_bytes();
+    (secret, hash)
+}
+
+#[test]
+fn test_join_private_game_with_code() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let (secret, hash) = join_code(&env, b"open sesame");
+
+    let options = GameOptions { join_code_hash: Some(hash.clone()), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    assert_eq!(client.get_game(&game_id).join_code_hash, Some(hash));
+
+    assert_eq!(client.try_join_game(&game_id, &player_o), Err(Ok(Error::JoinCodeRequired)));
+    let wrong = Bytes::from_slice(&env, b"open barley");
+    assert_eq!(client.try_join_game_with_code(&game_id, &player_o, &wrong), Err(Ok(Error::InvalidJoinCode)));
+
+    let game = client.join_game_with_code(&game_id, &player_o, &secret);
+    assert_eq!(game.player_o, Some(player_o.clone()));
+
+    client.make_move(&game_id, &player_x, &4);
+    assert_eq!(client.get_board(&game_id).get(4).unwrap(), Some(Player::X));
+}
+
+#[test]
+fn test_join_public_game_with_code_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let (secret, _) = join_code(&env, b"open sesame");
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_join_game_with_code(&game_id, &player_o, &secret), Err(Ok(Error::InvalidJoinCode)));
+    client.join_game(&game_id, &player_o);
+}
+
+#[test]
+fn test_private_games_hidden_from_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let (secret, hash) = join_code(&env, b"open sesame");
+
+    let public_id = client.create_game(&player_x, &GameOptions::default());
+    let private_id = client.create_game(&player_x, &GameOptions { join_code_hash: Some(hash), ..Default::default() });
+
+    let games = client.list_games();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games.get(0).unwrap().id, public_id);
+
+    let page = client.list_games_page(&0, &10, &GameFilter::any(&env));
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, public_id);
+
+    // Joining does not make the game public
+    client.join_game_with_code(&private_id, &player_o, &secret);
+    assert_eq!(client.list_games().len(), 1);
+
+    // Participants still see it, and anyone can read it by ID
+    let page = client.get_player_games(&player_o, &Vec::new(&env), &0, &10);
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, private_id);
+    assert!(page.games.get(0).unwrap().private);
+    assert_eq!(client.get_game(&private_id).player_o, Some(player_o));
+}
+
+// === SIDE SELECTION TESTS ===
+
+#[test]
+fn test_creator_plays_o() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let creator = Address::generate(&env);
+    let joiner = Address::generate(&env);
+    native.mint(&creator, &100);
+    native.mint(&joiner, &100);
+    client.deposit_native(&creator, &100);
+    client.deposit_native(&joiner, &100);
+
+    let options = GameOptions { creator_side: SideChoice::O, ..Default::default() };
+    let game_id = client.create_game_with_bet(&creator, &100, &TokenType::Native, &options);
+
+    // The joiner takes the X seat and, with the default first move, starts
+    let game = client.join_game(&game_id, &joiner);
+    assert_eq!(game.player_x, joiner);
+    assert_eq!(game.player_o, Some(creator.clone()));
+    assert_eq!(game.current_player, Player::X);
+    assert_eq!(client.try_make_move(&game_id, &creator, &0), Err(Ok(Error::NotYourTurn)));
+
+    // Creator wins as O and collects the pot
+    client.make_move(&game_id, &joiner, &0);
+    client.make_move(&game_id, &creator, &3);
+    client.make_move(&game_id, &joiner, &1);
+    client.make_move(&game_id, &creator, &4);
+    client.make_move(&game_id, &joiner, &8);
+    let game = client.make_move(&game_id, &creator, &5);
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(client.get_board(&game_id).get(3).unwrap(), Some(Player::O));
+
+    assert_eq!(client.try_claim_rewards(&game_id, &joiner), Err(Ok(Error::PlayerCannotClaim)));
+    client.claim_rewards(&game_id, &creator);
+    assert_eq!(client.get_balance(&creator).native, 200);
+}
+
+#[test]
+fn test_o_moves_first() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let options = GameOptions { first_move: SideChoice::O, ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    let game = client.join_game(&game_id, &player_o);
+    assert_eq!(game.player_x, player_x);
+    assert_eq!(game.current_player, Player::O);
+
+    assert_eq!(client.try_make_move(&game_id, &player_x, &0), Err(Ok(Error::NotYourTurn)));
+    let game = client.make_move(&game_id, &player_o, &0);
+    assert_eq!(game.board_o, Bytes::from_array(&env, &[0b0000_0001, 0]));
+    assert_eq!(game.current_player, Player::X);
+}
+
+#[test]
+fn test_random_sides_settled_on_join() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let creator = Address::generate(&env);
+    let options = GameOptions { creator_side: SideChoice::Random, first_move: SideChoice::Random, ..Default::default() };
+
+    let mut creator_sides = std::vec::Vec::new();
+    let mut first_moves = std::vec::Vec::new();
+    for _ in 0..16 {
+        let game_id = client.create_game(&creator, &options);
+
+        // Nothing is decided while the game waits for an opponent
+        let page = client.get_player_games(&creator, &Vec::new(&env), &(game_id - 1), &1);
+        let info = page.games.get(0).unwrap();
+        assert_eq!(info.creator_side, SideChoice::Random);
+        assert_eq!(info.first_move, SideChoice::Random);
+
+        let joiner = Address::generate(&env);
+        let game = client.join_game(&game_id, &joiner);
+        let creator_side = game.creator_side.clone();
+        match creator_side {
+            SideChoice::X => assert_eq!((game.player_x, game.player_o), (creator.clone(), Some(joiner))),
+            SideChoice::O => assert_eq!((game.player_x, game.player_o), (joiner, Some(creator.clone()))),
+            SideChoice::Random => panic!("creator side left unresolved"),
+        }
+        assert_eq!(game.first_move, game.current_player.clone().into());
+
+        let page = client.get_player_games(&creator, &Vec::new(&env), &(game_id - 1), &1);
+        assert_eq!(page.games.get(0).unwrap().creator_side, creator_side);
+
+        creator_sides.push(creator_side);
+        first_moves.push(game.first_move);
+    }
+
+    assert!(creator_sides.contains(&SideChoice::X) && creator_sides.contains(&SideChoice::O));
+    assert!(first_moves.contains(&SideChoice::X) && first_moves.contains(&SideChoice::O));
+}
+
+// === RESIGNATION AND DRAW OFFER TESTS ===
+
+#[test]
+fn test_resign_awards_opponent_and_bet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &250, &TokenType::Native, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    client.make_move(&game_id, &player_x, &4);
+
+    // Resigning is allowed on either player's turn
+    let game = client.resign(&game_id, &player_x);
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(client.try_resign(&game_id, &player_o), Err(Ok(Error::GameAlreadyFinished)));
+
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_o).native, 1250);
+    assert_eq!(client.get_balance(&player_x).native, 750);
+}
+
+#[test]
+fn test_accept_draw_settles_bets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    client.make_move(&game_id, &player_x, &4);
+
+    let game = client.offer_draw(&game_id, &player_o);
+    assert_eq!(game.draw_offer, Some(player_o.clone()));
+    assert_eq!(
+        client.try_accept_draw(&game_id, &player_o),
+        Err(Ok(Error::InvalidOffer))
+    );
+
+    let game = client.accept_draw(&game_id, &player_x);
+    assert_eq!(game.status, GameStatus::Draw);
+    assert_eq!(game.draw_offer, None);
+
+    client.claim_rewards(&game_id, &player_x);
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_x).native, 1000);
+    assert_eq!(client.get_balance(&player_o).native, 1000);
+}
+
+#[test]
+fn test_decline_draw_continues_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+
+    assert_eq!(client.try_accept_draw(&game_id, &player_o), Err(Ok(Error::NoDrawOffer)));
+
+    client.offer_draw(&game_id, &player_x);
+    assert_eq!(client.try_offer_draw(&game_id, &player_o), Err(Ok(Error::InvalidOffer)));
+
+    let game = client.decline_draw(&game_id, &player_o);
+    assert_eq!(game.status, GameStatus::InProgress);
+    assert_eq!(game.draw_offer, None);
+    assert_eq!(client.try_decline_draw(&game_id, &player_o), Err(Ok(Error::NoDrawOffer)));
+
+    // A move withdraws a pending offer
+    client.offer_draw(&game_id, &player_x);
+    let game = client.make_move(&game_id, &player_x, &0);
+    assert_eq!(game.draw_offer, None);
+}
+
+#[test]
+fn test_resign_and_draw_require_participants() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_resign(&game_id, &player_x), Err(Ok(Error::GameNeedsSecondPlayer)));
+    assert_eq!(client.try_offer_draw(&game_id, &player_x), Err(Ok(Error::GameNeedsSecondPlayer)));
+
+    client.join_game(&game_id, &player_o);
+    assert_eq!(client.try_resign(&game_id, &outsider), Err(Ok(Error::NotAPlayer)));
+    assert_eq!(client.try_offer_draw(&game_id, &outsider), Err(Ok(Error::NotAPlayer)));
+}
+
+// === REMATCH TESTS ===
+
+#[test]
+fn test_rematch_swaps_sides() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    client.resign(&game_id, &player_o);
+    client.claim_rewards(&game_id, &player_x);
+    native.mint(&player_o, &100);
+    client.deposit_native(&player_o, &100);
+
+    client.offer_rematch(&game_id, &player_o, &RematchOptions::default());
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("rm_offer"), game_id).into_val(&env),
+                player_o.into_val(&env),
+            ),
+        ]
+    );
+
+    let rematch_id = client.accept_rematch(&game_id, &player_x);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("created"), rematch_id).into_val(&env),
+                player_o.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("bet_lock"), rematch_id).into_val(&env),
+                (player_o.clone(), 100i128, TokenType::Native).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("bet_lock"), rematch_id).into_val(&env),
+                (player_x.clone(), 100i128, TokenType::Native).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("joined"), rematch_id).into_val(&env),
+                player_x.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("rematch"), game_id).into_val(&env),
+                (player_x.clone(), rematch_id).into_val(&env),
+            ),
+        ]
+    );
+
+    // The loser of the first game now plays X and moves first
+    let rematch = client.get_game(&rematch_id);
+    assert_eq!(rematch.player_x, player_o);
+    assert_eq!(rematch.player_o, Some(player_x.clone()));
+    assert_eq!(rematch.current_player, Player::X);
+    assert_eq!(rematch.status, GameStatus::InProgress);
+    assert_eq!(rematch.previous_game_id, Some(game_id));
+    assert_eq!(client.get_game(&game_id).rematch_game_id, Some(rematch_id));
+
+    let bet = client.get_game_bet(&rematch_id).unwrap();
+    assert_eq!(bet.amount, 100);
+    assert_eq!(bet.token_type, TokenType::Native);
+    assert!(bet.player_x_paid && bet.player_o_paid);
+    assert_eq!(client.get_balance(&player_x).native, 100);
+    assert_eq!(client.get_balance(&player_o).native, 0);
+
+    // Both players see the rematch
+    for player in [&player_x, &player_o] {
+        let page = client.get_player_games(player, &Vec::new(&env), &0, &10);
+        assert_eq!(page.games.len(), 2);
+        assert_eq!(page.games.get(1).unwrap().id, rematch_id);
+    }
+
+    client.make_move(&rematch_id, &player_o, &4);
+}
+
+#[test]
+fn test_rematch_rolls_over_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    // Player X wins and does not claim before offering the rematch
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    client.resign(&game_id, &player_o);
+    native.mint(&player_o, &100);
+    client.deposit_native(&player_o, &100);
+
+    let options = RematchOptions { keep_sides: true, roll_over: true };
+    client.offer_rematch(&game_id, &player_x, &options);
+    let rematch_id = client.accept_rematch(&game_id, &player_o);
+
+    let bet = client.get_game_bet(&game_id).unwrap();
+    assert!(bet.player_x_claimed && bet.rewards_claimed);
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::RewardsAlreadyClaimed)));
+
+    // The 200 payout funds player X's new stake, leaving the rest free
+    let rematch = client.get_game(&rematch_id);
+    assert_eq!(rematch.player_x, player_x);
+    let bet = client.get_game_bet(&rematch_id).unwrap();
+    assert!(bet.player_x_paid && bet.player_o_paid);
+    assert_eq!(client.get_balance(&player_x).native, 100);
+    assert_eq!(client.get_balance(&player_o).native, 0);
+}
+
+#[test]
+fn test_rematch_requires_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    client.resign(&game_id, &player_o);
+
+    // The winner's payout stays unclaimed and player O has nothing left
+    let options = RematchOptions { keep_sides: false, roll_over: true };
+    client.offer_rematch(&game_id, &player_x, &options);
+    assert_eq!(
+        client.try_accept_rematch(&game_id, &player_o),
+        Err(Ok(Error::InsufficientNativeBalanceForBet))
+    );
+    assert!(!client.get_game_bet(&game_id).unwrap().player_x_claimed);
+    assert_eq!(client.get_game(&game_id).rematch_game_id, None);
+}
+
+#[test]
+fn test_rematch_invalid_requests() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let options = RematchOptions::default();
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    assert_eq!(
+        client.try_offer_rematch(&game_id, &player_x, &options),
+        Err(Ok(Error::GameStillInProgress))
+    );
+
+    client.resign(&game_id, &player_x);
+    assert_eq!(client.try_offer_rematch(&game_id, &stranger, &options), Err(Ok(Error::NotAPlayer)));
+    assert_eq!(client.try_accept_rematch(&game_id, &player_o), Err(Ok(Error::NoRematchOffer)));
+
+    client.offer_rematch(&game_id, &player_x, &options);
+    assert_eq!(
+        client.try_accept_rematch(&game_id, &player_x),
+        Err(Ok(Error::InvalidOffer))
+    );
+    let rematch_id = client.accept_rematch(&game_id, &player_o);
+    assert!(!client.get_game(&rematch_id).has_bet);
+
+    // A finished game is rematched at most once
+    assert_eq!(
+        client.try_offer_rematch(&game_id, &player_o, &options),
+        Err(Ok(Error::InvalidOffer))
+    );
+    assert_eq!(client.try_accept_rematch(&game_id, &player_o), Err(Ok(Error::InvalidOffer)));
+
+    let cancelled = client.create_game(&player_x, &GameOptions::default());
+    client.cancel_game(&cancelled, &player_x);
+    assert_eq!(client.try_offer_rematch(&cancelled, &player_x, &options), Err(Ok(Error::GameCancelled)));
+}
+
+#[test]
+fn test_cancel_rematch_offer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    // Player X wins and offers to roll the unclaimed payout into a rematch
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    client.resign(&game_id, &player_o);
+    native.mint(&player_o, &100);
+    client.deposit_native(&player_o, &100);
+    let options = RematchOptions { keep_sides: true, roll_over: true };
+    client.offer_rematch(&game_id, &player_x, &options);
+
+    // Only the player who made the offer can withdraw it
+    assert_eq!(client.try_cancel_rematch_offer(&game_id, &player_o), Err(Ok(Error::NoRematchOffer)));
+    client.cancel_rematch_offer(&game_id, &player_x);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("rm_cancel"), game_id).into_val(&env),
+                player_x.into_val(&env),
+            ),
+        ]
+    );
+
+    // The withdrawn offer can no longer lock stakes or claim the payout
+    assert_eq!(client.try_accept_rematch(&game_id, &player_o), Err(Ok(Error::NoRematchOffer)));
+    assert_eq!(client.try_cancel_rematch_offer(&game_id, &player_x), Err(Ok(Error::NoRematchOffer)));
+    assert!(!client.get_game_bet(&game_id).unwrap().player_x_claimed);
+    assert_eq!(client.get_balance(&player_o).native, 100);
+    assert_eq!(client.get_game(&game_id).rematch_game_id, None);
+
+    // A fresh offer can still be made and accepted
+    client.offer_rematch(&game_id, &player_o, &options);
+    let rematch_id = client.accept_rematch(&game_id, &player_x);
+    assert_eq!(client.get_game(&game_id).rematch_game_id, Some(rematch_id));
+}
+
+// === TIMEOUT TESTS ===
+
+#[test]
+fn test_move_records_ledger_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    let game = client.get_game(&game_id);
+    assert_eq!(game.move_timeout, DEFAULT_MOVE_TIMEOUT);
+
+    env.ledger().set_timestamp(1_000);
+    env.ledger().set_sequence_number(50);
+    let game = client.join_game(&game_id, &player_o);
+    assert_eq!(game.last_move_timestamp, 1_000);
+    assert_eq!(game.last_move_ledger, 50);
+
+    env.ledger().set_timestamp(1_250);
+    env.ledger().set_sequence_number(60);
+    let game = client.make_move(&game_id, &player_x, &4);
+    assert_eq!(game.last_move_timestamp, 1_250);
+    assert_eq!(game.last_move_ledger, 60);
+}
+
+#[test]
+fn test_claim_timeout_win() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let options = GameOptions { move_timeout: Some(3_600), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    env.ledger().set_timestamp(1_000);
+    client.join_game(&game_id, &player_o);
+    client.make_move(&game_id, &player_x, &4);
+
+    // O still has time to move
+    env.ledger().set_timestamp(4_600);
+    assert_eq!(
+        client.try_claim_timeout_win(&game_id, &player_x),
+        Err(Ok(Error::MoveDeadlineNotReached))
+    );
+
+    env.ledger().set_timestamp(4_601);
+    let game = client.claim_timeout_win(&game_id, &player_x);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(
+        client.try_make_move(&game_id, &player_o, &0),
+        Err(Ok(Error::GameAlreadyFinished))
+    );
+}
+
+#[test]
+fn test_timeout_win_pays_out_bet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let options = GameOptions { move_timeout: Some(600), ..Default::default() };
+    let game_id = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &options);
+    env.ledger().set_timestamp(10_000);
+    client.join_game(&game_id, &player_o);
+
+    // X never makes the first move
+    env.ledger().set_timestamp(10_601);
+    let game = client.claim_timeout_win(&game_id, &player_o);
+    assert_eq!(game.status, GameStatus::OWins);
+
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::PlayerCannotClaim)));
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_o).native, 1300);
+    assert_eq!(client.get_balance(&player_x).native, 700);
+}
+
+#[test]
+fn test_claim_timeout_win_invalid_claims() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    let options = GameOptions { move_timeout: Some(60), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(
+        client.try_claim_timeout_win(&game_id, &player_x),
+        Err(Ok(Error::GameNeedsSecondPlayer))
+    );
+
+    client.join_game(&game_id, &player_o);
+    env.ledger().set_timestamp(2_000);
+
+    // X is on move, so only O may claim the timeout
+    assert_eq!(
+        client.try_claim_timeout_win(&game_id, &player_x),
+        Err(Ok(Error::CannotClaimTimeoutOnOwnTurn))
+    );
+    assert_eq!(client.try_claim_timeout_win(&game_id, &outsider), Err(Ok(Error::NotAPlayer)));
+}
+
+#[test]
+fn test_create_game_zero_timeout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let options = GameOptions { move_timeout: Some(0), ..Default::default() };
+    assert_eq!(client.try_create_game(&player_x, &options), Err(Ok(Error::InvalidMoveTimeout)));
+}
+
+// === BOARD SIZE TESTS ===
+
+/// Positions of a line of `length` cells from `start` (row, column), moving by `step` each cell
+fn line_positions(size: u32, start: (u32, u32), step: (i32, i32), length: u32) -> std::vec::Vec<u32> {
+    (0..length as i32)
+        .map(|i| {
+            let row = start.0 as i32 + i * step.0;
+            let column = start.1 as i32 + i * step.1;
+            (row * size as i32 + column) as u32
+        })
+        .collect()
+}
+
+/// Play a game in which X completes `line`, finishing on its middle cell,
+/// while O fills the first free cells elsewhere
+fn play_line_win(env: &Env, client: &TicTacToeContractClient, options: &GameOptions, line: &[u32]) -> Game {
+    let player_x = Address::generate(env);
+    let player_o = Address::generate(env);
+    let game_id = client.create_game(&player_x, options);
+    client.join_game(&game_id, &player_o);
+
+    let mut x_moves = line.to_vec();
+    let middle = x_moves.remove(line.len() / 2);
+    x_moves.push(middle);
+    let mut o_moves = (0..).filter(|position| !line.contains(position));
+
+    for (i, position) in x_moves.iter().enumerate() {
+        let game = client.make_move(&game_id, &player_x, position);
+        if i + 1 < x_moves.len() {
+            assert_eq!(game.status, GameStatus::InProgress);
+            client.make_move(&game_id, &player_o, &o_moves.next().unwrap());
+        }
+    }
+    client.get_game(&game_id)
+}
+
+#[test]
+fn test_k_in_a_row_every_board_size() {
+    let env = env_without_snapshot();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    for size in MIN_BOARD_SIZE..=MAX_BOARD_SIZE {
+        let options = GameOptions { size: Some(size), ..Default::default() };
+        let k = size.min(DEFAULT_MAX_WIN_LENGTH);
+
+        // A row, a column and both diagonals, each touching an edge of the board
+        let lines = [
+            line_positions(size, (size / 2, size - k), (0, 1), k),
+            line_positions(size, (size - k, 0), (1, 0), k),
+            line_positions(size, (size - k, size - k), (1, 1), k),
+            line_positions(size, (0, k - 1), (1, -1), k),
+        ];
+        for line in lines {
+            let game = play_line_win(&env, &client, &options, &line);
+            assert_eq!((game.size, game.win_length), (size, k));
+            assert_eq!(game.status, GameStatus::XWins, "size {} line {:?}", size, line);
+            assert_eq!(game.winning_line, Vec::from_slice(&env, &line));
+        }
+
+    }
+}
+
+#[test]
+fn test_large_board_positions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let options = GameOptions { size: Some(MAX_BOARD_SIZE), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+
+    let cells = MAX_BOARD_SIZE * MAX_BOARD_SIZE;
+    assert_eq!(client.get_board(&game_id).len(), cells);
+    assert_eq!(client.try_make_move(&game_id, &player_x, &cells), Err(Ok(Error::InvalidPosition)));
+
+    client.make_move(&game_id, &player_x, &(cells - 1));
+    client.make_move(&game_id, &player_o, &0);
+    let board = client.get_board(&game_id);
+    assert_eq!(board.get(cells - 1).unwrap(), Some(Player::X));
+    assert_eq!(board.get(0).unwrap(), Some(Player::O));
+    assert_eq!(
+        client.try_make_move(&game_id, &player_x, &0),
+        Err(Ok(Error::PositionAlreadyTaken))
+    );
+}
+
+#[test]
+fn test_custom_win_length() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    // Four in a row on 6x6; a longer run also wins and is reported whole
+    let options = GameOptions { size: Some(6), win_length: Some(4), ..Default::default() };
+    let game = play_line_win(&env, &client, &options, &[0, 1, 2, 3]);
+    assert_eq!(game.status, GameStatus::XWins);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+    for (x, o) in [(0u32, 30u32), (1, 31), (3, 33), (4, 34)] {
+        client.make_move(&game_id, &player_x, &x);
+        client.make_move(&game_id, &player_o, &o);
+    }
+    let game = client.make_move(&game_id, &player_x, &2);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 1, 2, 3, 4]);
+
+    // Runs broken by the board edge do not count
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+    for (x, o) in [(4u32, 30u32), (5, 31), (6, 33)] {
+        client.make_move(&game_id, &player_x, &x);
+        client.make_move(&game_id, &player_o, &o);
+    }
+    let game = client.make_move(&game_id, &player_x, &7);
+    assert_eq!(game.status, GameStatus::InProgress);
+}
+
+#[test]
+fn test_large_board_draw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions { size: Some(4), ..Default::default() });
+    client.join_game(&game_id, &player_o);
+
+    // XXOO / OOXX / XXOO / OOXX leaves nobody four in a row
+    let x_moves = [0u32, 1, 6, 7, 8, 9, 14, 15];
+    let o_moves = [2u32, 3, 4, 5, 10, 11, 12, 13];
+    for (x, o) in x_moves.iter().zip(o_moves.iter()) {
+        client.make_move(&game_id, &player_x, x);
+        client.make_move(&game_id, &player_o, o);
+    }
+
+    let game = client.get_game(&game_id);
+    assert_eq!(game.status, GameStatus::Draw);
+    assert!(game.winning_line.is_empty());
+}
+
+#[test]
+fn test_invalid_board_options() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    for (size, win_length, error) in [
+        (Some(MIN_BOARD_SIZE - 1), None, Error::InvalidBoardSize),
+        (Some(MAX_BOARD_SIZE + 1), None, Error::InvalidBoardSize),
+        (Some(5), Some(2), Error::InvalidWinLength),
+        (Some(5), Some(6), Error::InvalidWinLength),
+        (None, Some(4), Error::InvalidWinLength),
+    ] {
+        let options = GameOptions { size, win_length, ..Default::default() };
+        assert_eq!(client.try_create_game(&player_x, &options), Err(Ok(error)));
+    }
+}
+
+// === MOVE HELPERS ===
+
+/// A move `play_game` can make: a position for `make_move`, or a
+/// (position, symbol) pair for `make_move_wild`
+trait TestMove {
+    fn play(&self, client: &TicTacToeContractClient, game_id: u32, player: &Address) -> Game;
+}
+
+impl TestMove for u32 {
+    fn play(&self, client: &TicTacToeContractClient, game_id: u32, player: &Address) -> Game {
+        client.make_move(&game_id, player, self)
+    }
+}
+
+impl TestMove for (u32, Player) {
+    fn play(&self, client: &TicTacToeContractClient, game_id: u32, player: &Address) -> Game {
+        client.make_move_wild(&game_id, player, &self.0, &self.1)
+    }
+}
+
+/// Play `moves`, alternating between `players` starting with the first
+fn play_game<M: TestMove>(
+    client: &TicTacToeContractClient,
+    game_id: u32,
+    players: (&Address, &Address),
+    moves: &[M],
+) -> Game {
+    let mut game = client.get_game(&game_id);
+    for (i, next) in moves.iter().enumerate() {
+        let player = if i % 2 == 0 { players.0 } else { players.1 };
+        game = next.play(client, game_id, player);
+    }
+    game
+}
+
+// === ULTIMATE TESTS ===
+
+/// Board position of `cell` within `sub_board` on the 9x9 Ultimate grid
+fn ultimate_position(sub_board: u32, cell: u32) -> u32 {
+    (sub_board / 3 * 3 + cell / 3) * 9 + sub_board % 3 * 3 + cell % 3
+}
+
+/// Board positions of (sub-board, cell) moves on the 9x9 Ultimate grid
+fn ultimate_positions(moves: &[(u32, u32)]) -> std::vec::Vec<u32> {
+    moves.iter().map(|(sub_board, cell)| ultimate_position(*sub_board, *cell)).collect()
+}
+
+#[test]
+fn test_ultimate_sends_opponent_to_sub_board() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let options = GameOptions { mode: GameMode::Ultimate, ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    let game = client.join_game(&game_id, &player_o);
+    assert_eq!(game.mode, GameMode::Ultimate);
+    assert_eq!((game.size, game.win_length), (ULTIMATE_BOARD_SIZE, SUB_BOARD_SIZE));
+    assert_eq!(client.get_board(&game_id).len(), 81);
+
+    let state = client.get_ultimate_state(&game_id).unwrap();
+    assert_eq!((state.closed, state.next_board), (0, None));
+
+    // Top-right cell of the top-left sub-board sends O to the top-right sub-board
+    client.make_move(&game_id, &player_x, &ultimate_position(0, 2));
+    assert_eq!(client.get_ultimate_state(&game_id).unwrap().next_board, Some(2));
+    assert_eq!(
+        client.try_make_move(&game_id, &player_o, &ultimate_position(1, 0)),
+        Err(Ok(Error::IllegalMove))
+    );
+
+    client.make_move(&game_id, &player_o, &ultimate_position(2, 4));
+    assert_eq!(client.get_ultimate_state(&game_id).unwrap().next_board, Some(4));
+    assert_eq!(client.get_board(&game_id).get(ultimate_position(2, 4)).unwrap(), Some(Player::O));
+
+    // Classic games have no Ultimate state
+    let classic_id = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.get_ultimate_state(&classic_id), None);
+}
+
+#[test]
+fn test_ultimate_game_with_bet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let options = GameOptions { mode: GameMode::Ultimate, ..Default::default() };
+    let (game_id, player_x, player_o) = start_betting_game_with(&env, &client, &native, 400, &options);
+    let players = (&player_x, &player_o);
+
+    // X takes the top-left sub-board down its right column
+    let moves = [(0, 2), (2, 1), (1, 3), (3, 2), (2, 5), (5, 1), (1, 4), (4, 0), (0, 5), (5, 0), (0, 8)];
+    play_game(&client, game_id, players, &ultimate_positions(&moves));
+    let state = client.get_ultimate_state(&game_id).unwrap();
+    assert_eq!(state.closed, 0b1);
+    assert_eq!(state.won_x, Bytes::from_array(&env, &[0b1, 0]));
+    assert_eq!(state.next_board, Some(8));
+
+    // O is sent to the closed top-left sub-board, so X may play anywhere open
+    let players_from_o = (&player_o, &player_x);
+    let moves = [(8, 2), (2, 8), (8, 8), (8, 3), (3, 0)];
+    play_game(&client, game_id, players_from_o, &ultimate_positions(&moves));
+    assert_eq!(client.get_ultimate_state(&game_id).unwrap().next_board, None);
+    assert_eq!(
+        client.try_make_move(&game_id, &player_x, &ultimate_position(0, 0)),
+        Err(Ok(Error::IllegalMove))
+    );
+
+    // Winning the top-right and then the top-middle sub-board completes the top row
+    let moves = [(2, 2), (4, 8), (8, 4), (4, 2), (1, 5)];
+    let game = play_game(&client, game_id, players, &ultimate_positions(&moves));
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 1, 2]);
+    assert_eq!(client.get_ultimate_state(&game_id).unwrap().closed, 0b111);
+
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.get_balance(&player_x).native, 800);
+    assert_eq!(client.get_balance(&player_o).native, 0);
+
+    // Rematches are played under the same rules
+    native.mint(&player_o, &400);
+    client.deposit_native(&player_o, &400);
+    client.offer_rematch(&game_id, &player_o, &RematchOptions::default());
+    let rematch_id = client.accept_rematch(&game_id, &player_x);
+    assert_eq!(client.get_game(&rematch_id).mode, GameMode::Ultimate);
+    assert_eq!(client.get_ultimate_state(&rematch_id).unwrap().closed, 0);
+}
+
+#[test]
+fn test_ultimate_draw_when_all_sub_boards_close_without_a_line() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let options = GameOptions { mode: GameMode::Ultimate, ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+
+    // X wins sub-boards 2, 4 and 5 and O wins 1, 3, 7 and 8, while the drawn
+    // corners 0 and 6 block every line that is left
+    let moves = [
+        (3, 3), (3, 2), (2, 2), (2, 4), (4, 6), (6, 2), (2, 6), (6, 7), (7, 1), (1, 8),
+        (8, 5), (5, 5), (5, 8), (8, 0), (0, 2), (2, 3), (3, 5), (5, 0), (0, 3), (3, 6),
+        (6, 4), (4, 7), (7, 7), (7, 0), (0, 1), (1, 6), (6, 8), (8, 2), (2, 8), (8, 4),
+        (4, 3), (3, 8), (8, 7), (7, 3), (3, 0), (0, 5), (5, 1), (1, 4), (4, 1), (1, 5),
+        (5, 7), (7, 2), (2, 0), (0, 4), (4, 5), (5, 4), (4, 8), (8, 3), (3, 7), (7, 5),
+        (5, 3), (3, 1), (1, 1), (1, 2), (2, 5), (5, 2), (6, 3), (3, 4), (4, 4), (0, 0),
+        (0, 8), (8, 6), (6, 6), (6, 0), (0, 7), (7, 6), (6, 1), (6, 5), (5, 6), (0, 6),
+    ];
+    let (last, rest) = moves.split_last().unwrap();
+    let game = play_game(&client, game_id, (&player_x, &player_o), &ultimate_positions(rest));
+    assert_eq!(game.status, GameStatus::InProgress);
+
+    let game = client.make_move(&game_id, &player_o, &ultimate_position(last.0, last.1));
+    assert_eq!(game.status, GameStatus::Draw);
+    assert!(game.winning_line.is_empty());
+
+    let state = client.get_ultimate_state(&game_id).unwrap();
+    assert_eq!(state.closed, 0b1_1111_1111);
+    assert_eq!(state.won_x, Bytes::from_array(&env, &[0b0011_0100, 0]));
+    assert_eq!(state.won_o, Bytes::from_array(&env, &[0b1000_1010, 1]));
+}
+
+#[test]
+fn test_ultimate_board_options() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    for (size, win_length, error) in [
+        (Some(5), None, Error::InvalidBoardSize),
+        (None, Some(4), Error::InvalidWinLength),
+    ] {
+        let options = GameOptions { mode: GameMode::Ultimate, size, win_length, ..Default::default() };
+        assert_eq!(client.try_create_game(&player_x, &options), Err(Ok(error)));
+    }
+
+    // Spelling out the fixed size and win length is allowed
+    let options = GameOptions {
+        mode: GameMode::Ultimate,
+        size: Some(ULTIMATE_BOARD_SIZE),
+        win_length: Some(SUB_BOARD_SIZE),
+        ..Default::default()
+    };
+    let game_id = client.create_game(&player_x, &options);
+    assert_eq!(client.get_game(&game_id).size, ULTIMATE_BOARD_SIZE);
+}
+
+// === MISÈRE TESTS ===
+
+#[test]
+fn test_misere_completing_line_loses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let options = GameOptions { misere: true, ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+
+    let info = client.list_games().get(0).unwrap();
+    assert!(info.misere);
+
+    let game = play_game(&client, game_id, (&player_x, &player_o), &[0, 3, 1, 4, 2]);
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 1, 2]);
+}
+
+#[test]
+fn test_misere_forced_loss_pays_opponent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let options = GameOptions { misere: true, ..Default::default() };
+    let (game_id, player_x, player_o) = start_betting_game_with(&env, &client, &native, 400, &options);
+
+    // X O X
+    // O X O
+    // O X .   X's only move is the last cell, completing the diagonal
+    let game = play_game(&client, game_id, (&player_x, &player_o), &[0, 1, 2, 3, 4, 5, 7, 6]);
+    assert_eq!(game.status, GameStatus::InProgress);
+    let game = client.make_move(&game_id, &player_x, &8);
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 4, 8]);
+
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::PlayerCannotClaim)));
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_o).native, 800);
+    assert_eq!(client.get_balance(&player_x).native, 0);
+
+    // Rematches keep misère rules
+    native.mint(&player_x, &400);
+    client.deposit_native(&player_x, &400);
+    client.offer_rematch(&game_id, &player_o, &RematchOptions::default());
+    let rematch_id = client.accept_rematch(&game_id, &player_x);
+    assert!(client.get_game(&rematch_id).misere);
+}
+
+#[test]
+fn test_misere_full_board_without_line_is_draw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions { misere: true, ..Default::default() });
+    client.join_game(&game_id, &player_o);
+
+    let game = play_game(&client, game_id, (&player_x, &player_o), &[0, 1, 2, 4, 3, 5, 7, 6, 8]);
+    assert_eq!(game.status, GameStatus::Draw);
+    assert!(game.winning_line.is_empty());
+}
+
+#[test]
+fn test_misere_ultimate_line_of_sub_boards_loses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let options = GameOptions { mode: GameMode::Ultimate, misere: true, ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    client.join_game(&game_id, &player_o);
+
+    // The moves of test_ultimate_game_with_bet: sub-boards still go to whoever
+    // completes them, but X's top row of sub-boards now loses
+    let moves = [
+        (0, 2), (2, 1), (1, 3), (3, 2), (2, 5), (5, 1), (1, 4), (4, 0), (0, 5), (5, 0), (0, 8),
+        (8, 2), (2, 8), (8, 8), (8, 3), (3, 0), (2, 2), (4, 8), (8, 4), (4, 2), (1, 5),
+    ];
+    let game = play_game(&client, game_id, (&player_x, &player_o), &ultimate_positions(&moves));
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 1, 2]);
+    assert_eq!(client.get_ultimate_state(&game_id).unwrap().won_x, Bytes::from_array(&env, &[0b111, 0]));
+}
+
+// === WILD TESTS ===
+
+#[test]
+fn test_wild_line_counts_for_mover() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    // X's seat completes a row of O marks: X wins, or loses under misère rules
+    for (misere, status) in [(false, GameStatus::XWins), (true, GameStatus::OWins)] {
+        let options = GameOptions { mode: GameMode::Wild, misere, ..Default::default() };
+        let game_id = client.create_game(&player_x, &options);
+        client.join_game(&game_id, &player_o);
+
+        let moves = [(0, Player::O), (4, Player::X), (1, Player::O), (8, Player::X)];
+        let game = play_game(&client, game_id, (&player_x, &player_o), &moves);
+        assert_eq!(game.status, GameStatus::InProgress);
+        assert_eq!(game.current_player, Player::X);
+
+        let game = client.make_move_wild(&game_id, &player_x, &2, &Player::O);
+        assert_eq!(game.status, status);
+        assert_eq!(game.winning_line, vec![&env, 0, 1, 2]);
+
+        let board = client.get_board(&game_id);
+        assert_eq!(board.get(4).unwrap(), Some(Player::X));
+        assert_eq!(board.get(2).unwrap(), Some(Player::O));
+
+        let history = client.get_moves(&game_id);
+        assert_eq!(history.get(0).unwrap().player, player_x);
+        assert_eq!(history.get(0).unwrap().symbol, Player::O);
+        assert_eq!(history.get(1).unwrap().symbol, Player::X);
+    }
+}
+
+#[test]
+fn test_wild_opponents_mark_wins_bet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let options = GameOptions { mode: GameMode::Wild, ..Default::default() };
+    let (game_id, player_x, player_o) = start_betting_game_with(&env, &client, &native, 400, &options);
+
+    // O's seat finishes the row X started
+    let moves = [(0, Player::X), (1, Player::X), (4, Player::O), (2, Player::X)];
+    let game = play_game(&client, game_id, (&player_x, &player_o), &moves);
+    assert_eq!(game.status, GameStatus::OWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 1, 2]);
+
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::PlayerCannotClaim)));
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_o).native, 800);
+}
+
+#[test]
+fn test_wild_draw_with_mixed_marks() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions { mode: GameMode::Wild, ..Default::default() });
+    client.join_game(&game_id, &player_o);
+
+    // X X O
+    // O O X
+    // X O X
+    let symbols = [Player::X, Player::X, Player::O, Player::O, Player::O, Player::X, Player::X, Player::O, Player::X];
+    let moves: std::vec::Vec<(u32, Player)> = symbols.into_iter().enumerate().map(|(i, symbol)| (i as u32, symbol)).collect();
+    let game = play_game(&client, game_id, (&player_x, &player_o), &moves);
+    assert_eq!(game.status, GameStatus::Draw);
+    assert!(game.winning_line.is_empty());
+}
+
+#[test]
+fn test_wild_move_paths() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let wild_id = client.create_game(&player_x, &GameOptions { mode: GameMode::Wild, ..Default::default() });
+    client.join_game(&wild_id, &player_o);
+    assert_eq!(client.try_make_move(&wild_id, &player_x, &0), Err(Ok(Error::IllegalMove)));
+    assert_eq!(
+        client.try_make_move_wild(&wild_id, &player_o, &0, &Player::O),
+        Err(Ok(Error::NotYourTurn))
+    );
+
+    client.make_move_wild(&wild_id, &player_x, &0, &Player::O);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("wild_move"), wild_id).into_val(&env),
+                (player_x.clone(), 0u32, Player::O).into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(
+        client.try_make_move_wild(&wild_id, &player_o, &0, &Player::X),
+        Err(Ok(Error::PositionAlreadyTaken))
+    );
+
+    // Other modes place the mover's own mark and take no symbol
+    for mode in [GameMode::Classic, GameMode::Ultimate] {
+        let game_id = client.create_game(&player_x, &GameOptions { mode, ..Default::default() });
+        client.join_game(&game_id, &player_o);
+        assert_eq!(
+            client.try_make_move_wild(&game_id, &player_x, &0, &Player::X),
+            Err(Ok(Error::IllegalMove))
+        );
+    }
+}
+
+// === CUBE TESTS ===
+
+/// Every line through a cube of side `size`, worked out apart from the
+/// contract: from each cell, each of the 13 directions whose first non-zero
+/// step is positive, keeping the lines that stay inside the cube
+fn cube_lines(size: i32) -> std::vec::Vec<std::vec::Vec<u32>> {
+    let mut directions = std::vec::Vec::new();
+    for z_step in -1..=1 {
+        for y_step in -1..=1 {
+            for x_step in -1..=1 {
+                if [z_step, y_step, x_step].into_iter().find(|step| *step != 0) == Some(1) {
+                    directions.push((z_step, y_step, x_step));
+                }
+            }
+        }
+    }
+    assert_eq!(directions.len(), 13);
+
+    let inside = |coordinate: i32| (0..size).contains(&coordinate);
+    let mut lines = std::vec::Vec::new();
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                for (z_step, y_step, x_step) in &directions {
+                    let cells: std::vec::Vec<(i32, i32, i32)> =
+                        (0..size).map(|i| (z + i * z_step, y + i * y_step, x + i * x_step)).collect();
+                    if cells.iter().all(|(z, y, x)| inside(*z) && inside(*y) && inside(*x)) {
+                        lines.push(cells.iter().map(|(z, y, x)| ((z * size + y) * size + x) as u32).collect());
+                    }
+                }
+            }
+        }
+    }
+    lines
+}
+
+#[test]
+fn test_cube_line_tables() {
+    let env = env_without_snapshot();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    for (size, line_count) in [(3, 49), (4, 76)] {
+        let lines = cube_lines(size);
+        assert_eq!(lines.len(), line_count);
+
+        let options = GameOptions { shape: BoardShape::Cube, size: Some(size as u32), ..Default::default() };
+        for line in lines {
+            let game = play_line_win(&env, &client, &options, &line);
+            assert_eq!(game.status, GameStatus::XWins, "size {} line {:?}", size, line);
+
+            let mut winning_line: std::vec::Vec<u32> = game.winning_line.iter().collect();
+            let mut expected = line.clone();
+            winning_line.sort();
+            expected.sort();
+            assert_eq!(winning_line, expected);
+        }
+    }
+}
+
+#[test]
+fn test_make_move_cube() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions { shape: BoardShape::Cube, ..Default::default() });
+    let game = client.join_game(&game_id, &player_o);
+    assert_eq!(game.shape, BoardShape::Cube);
+    assert_eq!((game.size, game.win_length), (MIN_CUBE_SIZE, MIN_CUBE_SIZE));
+    assert_eq!(client.get_board(&game_id).len(), 27);
+
+    // Column 1, row 2 of the bottom layer, then the first cell of the top layer
+    client.make_move_cube(&game_id, &player_x, &1, &2, &0);
+    client.make_move_cube(&game_id, &player_o, &0, &0, &2);
+    let board = client.get_board(&game_id);
+    assert_eq!(board.get(7).unwrap(), Some(Player::X));
+    assert_eq!(board.get(18).unwrap(), Some(Player::O));
+    assert_eq!(client.get_moves(&game_id).get(0).unwrap().position, 7);
+
+    assert_eq!(client.try_make_move_cube(&game_id, &player_x, &3, &0, &0), Err(Ok(Error::InvalidPosition)));
+    assert_eq!(client.try_make_move(&game_id, &player_x, &27), Err(Ok(Error::InvalidPosition)));
+    assert_eq!(
+        client.try_make_move_cube(&game_id, &player_x, &1, &2, &0),
+        Err(Ok(Error::PositionAlreadyTaken))
+    );
+
+    // Flat positions work on cubes too
+    client.make_move(&game_id, &player_x, &26);
+    assert_eq!(client.get_board(&game_id).get(26).unwrap(), Some(Player::X));
+
+    let square_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&square_id, &player_o);
+    assert_eq!(client.try_make_move_cube(&square_id, &player_x, &0, &0, &0), Err(Ok(Error::InvalidPosition)));
+}
+
+#[test]
+fn test_cube_board_options() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    for (mode, size, win_length, error) in [
+        (GameMode::Classic, Some(MIN_CUBE_SIZE - 1), None, Error::InvalidBoardSize),
+        (GameMode::Classic, Some(MAX_CUBE_SIZE + 1), None, Error::InvalidBoardSize),
+        (GameMode::Classic, Some(4), Some(3), Error::InvalidWinLength),
+        (GameMode::Ultimate, None, None, Error::InvalidBoardSize),
+    ] {
+        let options = GameOptions { mode, shape: BoardShape::Cube, size, win_length, ..Default::default() };
+        assert_eq!(client.try_create_game(&player_x, &options), Err(Ok(error)));
+    }
+
+    let options = GameOptions { shape: BoardShape::Cube, size: Some(4), ..Default::default() };
+    let game_id = client.create_game(&player_x, &options);
+    let info = client.list_games().get(0).unwrap();
+    assert_eq!((info.id, info.shape, info.size, info.win_length), (game_id, BoardShape::Cube, 4, 4));
+}
+
+#[test]
+fn test_qubic_game_with_bet() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let options = GameOptions { shape: BoardShape::Cube, size: Some(4), ..Default::default() };
+    let (game_id, player_x, player_o) = start_betting_game_with(&env, &client, &native, 400, &options);
+
+    // X takes the space diagonal while O strings three along an edge
+    for i in 0..3 {
+        client.make_move_cube(&game_id, &player_x, &i, &i, &i);
+        client.make_move_cube(&game_id, &player_o, &(i + 1), &0, &0);
+    }
+    let game = client.make_move_cube(&game_id, &player_x, &3, &3, &3);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(game.winning_line, vec![&env, 0, 21, 42, 63]);
+
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.get_balance(&player_x).native, 800);
+    assert_eq!(client.get_balance(&player_o).native, 0);
+}
+
+// === MOVE HISTORY TESTS ===
+
+#[test]
+fn test_get_moves_records_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    assert!(client.get_moves(&game_id).is_empty());
+
+    let moves = [(&player_x, 2u32), (&player_o, 0), (&player_x, 4), (&player_o, 1), (&player_x, 6)];
+    for (i, (player, position)) in moves.iter().enumerate() {
+        env.ledger().set_sequence_number(100 + i as u32);
+        env.ledger().set_timestamp(5_000 + 10 * i as u64);
+        client.make_move(&game_id, player, position);
+    }
+
+    let history = client.get_moves(&game_id);
+    assert_eq!(history.len(), 5);
+    for (i, (player, position)) in moves.iter().enumerate() {
+        let recorded = history.get(i as u32).unwrap();
+        assert_eq!(
+            recorded,
+            Move {
+                player: (*player).clone(),
+                position: *position,
+                symbol: if i % 2 == 0 { Player::X } else { Player::O },
+                ledger: 100 + i as u32,
+                timestamp: 5_000 + 10 * i as u64,
+            }
+        );
+    }
+
+    let game = client.get_game(&game_id);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(game.winning_line, vec![&env, 2, 4, 6]);
+
+    // History is extended along with the game
+    assert!(persistent_ttl(&env, &client, &DataKey::GameMoves(game_id)) > SETTLED_GAME_TTL);
+}
+
+#[test]
+fn test_winning_line_only_for_wins_on_board() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let resigned = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&resigned, &player_o);
+    client.make_move(&resigned, &player_x, &0);
+    let game = client.resign(&resigned, &player_o);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert!(game.winning_line.is_empty());
+    assert_eq!(client.get_moves(&resigned).len(), 1);
+
+    let drawn = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&drawn, &player_o);
+    for (player, position) in [
+        (&player_x, 0u32),
+        (&player_o, 1),
+        (&player_x, 2),
+        (&player_o, 4),
+        (&player_x, 3),
+        (&player_o, 5),
+        (&player_x, 7),
+        (&player_o, 6),
+        (&player_x, 8),
+    ] {
+        client.make_move(&drawn, player, &position);
+    }
+    let game = client.get_game(&drawn);
+    assert_eq!(game.status, GameStatus::Draw);
+    assert!(game.winning_line.is_empty());
+    assert_eq!(client.get_moves(&drawn).len(), 9);
+
+    assert_eq!(client.try_get_moves(&99), Err(Ok(Error::GameNotFound)));
+}
+
+// === BUDGET TESTS ===
+
+// Network per-transaction limits
+const MAX_CPU: u64 = 100_000_000;
+const MAX_MEMORY: u64 = 40 * 1024 * 1024;
+
+/// CPU instructions and memory bytes spent by `work`, measured from a fresh budget
+fn measure_cost(env: &Env, work: impl FnOnce()) -> (u64, u64) {
+    env.cost_estimate().budget().reset_default();
+    work();
+    let budget = env.cost_estimate().budget();
+    (budget.cpu_instruction_cost(), budget.memory_bytes_cost())
+}
+
+#[test]
+fn test_make_move_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+
+    // Every move, including the winning one, stays far inside the limits
+    for (player, position) in [(&player_x, 0u32), (&player_o, 3), (&player_x, 1), (&player_o, 4), (&player_x, 2)] {
+        let (cpu, memory) = measure_cost(&env, || {
+            client.make_move(&game_id, player, &position);
+        });
+        assert!(cpu < MAX_CPU / 100);
+        assert!(memory < MAX_MEMORY / 100);
+    }
+    assert_eq!(client.get_game(&game_id).status, GameStatus::XWins);
+}
+
+#[test]
+fn test_bitboard_cheaper_than_vec_board() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    for (player, position) in [(&player_x, 0u32), (&player_o, 3), (&player_x, 1), (&player_o, 4)] {
+        client.make_move(&game_id, player, &position);
+    }
+
+    // The same position stored both ways: as the schema 1 layout's one host
+    // object per cell, and as the bitsets games use now. Only the board is
+    // stored, so fields added to `Game` since do not skew the comparison.
+    let game = client.get_game(&game_id);
+    let cells = client.get_board(&game_id);
+    let vec_key = DataKey::Game(game_id + 1);
+    let bit_key = DataKey::Game(game_id + 2);
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(&vec_key, &cells);
+        env.storage().persistent().set(&bit_key, &(game.board_x, game.board_o));
+    });
+
+    // The board work of X's winning move on 2: load, mark, look for a win, store
+    let (vec_cpu, vec_memory) = env.as_contract(&client.address, || {
+        measure_cost(&env, || {
+            let mut board: Vec<Option<Player>> = env.storage().persistent().get(&vec_key).unwrap();
+            board.set(2, Some(Player::X));
+            let lines = [[0, 1, 2], [3, 4, 5], [6, 7, 8], [0, 3, 6], [1, 4, 7], [2, 5, 8], [0, 4, 8], [2, 4, 6]];
+            let won = lines
+                .iter()
+                .any(|line| line.iter().all(|position| board.get(*position).unwrap() == Some(Player::X)));
+            assert!(won);
+            env.storage().persistent().set(&vec_key, &board);
+        })
+    });
+    let (bit_cpu, bit_memory) = env.as_contract(&client.address, || {
+        measure_cost(&env, || {
+            let (board_x, board_o): (Bytes, Bytes) = env.storage().persistent().get(&bit_key).unwrap();
+            let mut board = Board::load(&BoardShape::Square, 3, &board_x, &board_o);
+            board.set(2, &Player::X);
+            assert_eq!(board.line_through(&env, 2, &Player::X, 3), Some(vec![&env, 0, 1, 2]));
+            env.storage().persistent().set(&bit_key, &board.to_bytes(&env));
+        })
+    });
+
+    assert!(bit_cpu < vec_cpu, "bitboard cpu {} vs vec board {}", bit_cpu, vec_cpu);
+    assert!(bit_memory < vec_memory, "bitboard memory {} vs vec board {}", bit_memory, vec_memory);
+}
+
+// === LISTING TESTS ===
+
+/// An `Env` that writes no test snapshot, for tests that build up far more
+/// storage than is worth keeping in the repository
+fn env_without_snapshot() -> Env {
+    Env::new_with_config(EnvTestConfig { capture_snapshot_at_drop: false })
+}
+
+/// Fill storage with `count` open games cloned from a real one, alternating
+/// between no bet and a native bet of `id` stroops
+fn seed_games(env: &Env, client: &TicTacToeContractClient, count: u32) {
+    let player_x = Address::generate(env);
+    let template = client.get_game(&client.create_game(&player_x, &GameOptions::default()));
+
+    // Seeding is test setup, so write in batches that fit one invocation each
+    for batch_start in (1..=count).step_by(20) {
+        env.cost_estimate().budget().reset_unlimited();
+        env.as_contract(&client.address, || {
+            let storage = env.storage().persistent();
+            for id in batch_start..(batch_start + 20).min(count + 1) {
+                let mut game = template.clone();
+                game.has_bet = id % 2 == 0;
+                if game.has_bet {
+                    let game_bet = GameBet {
+                        amount: id as i128,
+                        token_type: TokenType::Native,
+                        player_x_paid: true,
+                        player_o_paid: false,
+                        rewards_claimed: false,
+                        player_x_claimed: false,
+                        player_o_claimed: false,
+                        fees: FeeConfig::default(),
+                    };
+                    storage.set(&DataKey::GameBet(id), &game_bet);
+                }
+                storage.set(&DataKey::Game(id), &game);
+            }
+            env.storage().instance().set(&DataKey::GameCounter, &count);
+        });
+    }
+}
+
+#[test]
+fn test_list_games_page_cursor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    for _ in 0..5 {
+        client.create_game(&player_x, &GameOptions::default());
+    }
+
+    let page = client.list_games_page(&0, &2, &GameFilter::any(&env));
+    assert_eq!(page.games.len(), 2);
+    assert_eq!(page.games.get(0).unwrap().id, 1);
+    assert_eq!(page.games.get(1).unwrap().id, 2);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.list_games_page(&2, &2, &GameFilter::any(&env));
+    assert_eq!(page.games.get(0).unwrap().id, 3);
+    assert_eq!(page.games.get(1).unwrap().id, 4);
+    assert_eq!(page.next_cursor, Some(4));
+
+    let page = client.list_games_page(&4, &2, &GameFilter::any(&env));
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, 5);
+    assert_eq!(page.next_cursor, None);
+
+    let page = client.list_games_page(&5, &2, &GameFilter::any(&env));
+    assert_eq!(page.games.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_list_games_page_invalid_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    assert_eq!(
+        client.try_list_games_page(&0, &0, &GameFilter::any(&env)),
+        Err(Ok(Error::InvalidPageLimit))
+    );
+    assert_eq!(
+        client.try_list_games_page(&0, &(MAX_PAGE_LIMIT + 1), &GameFilter::any(&env)),
+        Err(Ok(Error::InvalidPageLimit))
+    );
+}
+
+#[test]
+fn test_list_games_page_filters() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let (token_address, token) = create_token_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+    token.mint(&player_x, &1000);
+    client.deposit_token(&player_x, &token_address, &1000);
+
+    // 1: open, no bet
+    let open_free = client.create_game(&player_x, &GameOptions::default());
+    // 2: open, native bet of 100
+    let open_native = client.create_game_with_bet(&player_x, &100, &TokenType::Native, &GameOptions::default());
+    // 3: open, token bet of 500
+    let open_token = client.create_game_with_bet(
+        &player_x,
+        &500,
+        &TokenType::Stellar(token_address.clone()),
+        &GameOptions::default(),
+    );
+    // 4: joined native bet of 300, won by X
+    let finished = client.create_game_with_bet(&player_x, &300, &TokenType::Native, &GameOptions::default());
+    client.join_game(&finished, &player_o);
+    client.resign(&finished, &player_o);
+    // 5: cancelled
+    let cancelled = client.create_game(&player_x, &GameOptions::default());
+    client.cancel_game(&cancelled, &player_x);
+
+    let ids = |filter: GameFilter| -> std::vec::Vec<u32> {
+        client
+            .list_games_page(&0, &MAX_PAGE_LIMIT, &filter)
+            .games
+            .iter()
+            .map(|info| info.id)
+            .collect()
+    };
+
+    // Default filter matches list_games
+    assert_eq!(ids(GameFilter::any(&env)), [open_free, open_native, open_token, finished]);
+
+    let filter = GameFilter { statuses: vec![&env, GameStatus::Cancelled], ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [cancelled]);
+
+    let filter = GameFilter { statuses: vec![&env, GameStatus::XWins], ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [finished]);
+
+    let filter = GameFilter { open_only: true, ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [open_free, open_native, open_token]);
+
+    let filter = GameFilter { has_bet: Some(false), ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [open_free]);
+
+    let filter = GameFilter { has_bet: Some(true), ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [open_native, open_token, finished]);
+
+    let filter = GameFilter { token_types: vec![&env, TokenType::Native], ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [open_native, finished]);
+
+    let filter = GameFilter {
+        token_types: vec![&env, TokenType::Stellar(token_address.clone())],
+        ..GameFilter::any(&env)
+    };
+    assert_eq!(ids(filter), [open_token]);
+
+    let filter = GameFilter { min_bet: Some(200), max_bet: Some(400), ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [finished]);
+
+    let filter = GameFilter { open_only: true, min_bet: Some(100), ..GameFilter::any(&env) };
+    assert_eq!(ids(filter), [open_native, open_token]);
+}
+
+#[test]
+fn test_list_games_page_skips_unmatched_games() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+    seed_games(&env, &client, 3 * MAX_PAGE_SCAN);
+
+    // Odd IDs have no bet, so a scan window holds MAX_PAGE_SCAN / 2 matches
+    // and the page stops short with a cursor at the end of the window
+    let filter = GameFilter { has_bet: Some(false), ..GameFilter::any(&env) };
+    let page = client.list_games_page(&0, &MAX_PAGE_LIMIT, &filter);
+    assert_eq!(page.games.len(), MAX_PAGE_LIMIT);
+    assert_eq!(page.next_cursor, Some(2 * MAX_PAGE_LIMIT - 1));
+
+    // Nothing matches: every call scans one window and reports how far it got
+    let filter = GameFilter { min_bet: Some(i128::MAX), ..GameFilter::any(&env) };
+    let page = client.list_games_page(&0, &MAX_PAGE_LIMIT, &filter);
+    assert_eq!(page.games.len(), 0);
+    assert_eq!(page.next_cursor, Some(MAX_PAGE_SCAN));
+
+    let page = client.list_games_page(&(2 * MAX_PAGE_SCAN), &MAX_PAGE_LIMIT, &filter);
+    assert_eq!(page.games.len(), 0);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_list_games_page_budget_with_many_games() {
+    let env = env_without_snapshot();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+    seed_games(&env, &client, 3000);
+
+    // Walking every page keeps each call well within the limits
+    let mut cursor = 0;
+    let mut seen = 0;
+    loop {
+        env.cost_estimate().budget().reset_default();
+        let page = client.list_games_page(&cursor, &MAX_PAGE_LIMIT, &GameFilter::any(&env));
+        assert!(env.cost_estimate().budget().cpu_instruction_cost() < MAX_CPU / 10);
+        assert!(env.cost_estimate().budget().memory_bytes_cost() < MAX_MEMORY / 10);
+        seen += page.games.len();
+        match page.next_cursor {
+            Some(next) => cursor = next,
+            None => break,
+        }
+    }
+    assert_eq!(seen, 3000);
+
+    // A page that scans a full window without matches is bounded the same way
+    env.cost_estimate().budget().reset_default();
+    let filter = GameFilter { min_bet: Some(i128::MAX), ..GameFilter::any(&env) };
+    let page = client.list_games_page(&2000, &MAX_PAGE_LIMIT, &filter);
+    assert_eq!(page.next_cursor, Some(2000 + MAX_PAGE_SCAN));
+    assert!(env.cost_estimate().budget().cpu_instruction_cost() < MAX_CPU / 10);
+    assert!(env.cost_estimate().budget().memory_bytes_cost() < MAX_MEMORY / 10);
+}
+
+#[test]
+fn test_get_player_games() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let game1 = client.create_game(&alice, &GameOptions::default());
+    let game2 = client.create_game(&bob, &GameOptions::default());
+    let game3 = client.create_game(&carol, &GameOptions::default());
+    let game4 = client.create_game(&alice, &GameOptions::default());
+    client.join_game(&game2, &alice);
+    client.join_game(&game3, &bob);
+    client.join_game(&game4, &carol);
+    client.resign(&game4, &carol);
+
+    let ids = |player: &Address, statuses: Vec<GameStatus>| -> std::vec::Vec<u32> {
+        client
+            .get_player_games(player, &statuses, &0, &MAX_PAGE_LIMIT)
+            .games
+            .iter()
+            .map(|info| info.id)
+            .collect()
+    };
+
+    // Ordered by when the player created or joined each game
+    assert_eq!(ids(&alice, vec![&env]), [game1, game4, game2]);
+    assert_eq!(ids(&bob, vec![&env]), [game2, game3]);
+    assert_eq!(ids(&carol, vec![&env]), [game3, game4]);
+    assert!(ids(&Address::generate(&env), vec![&env]).is_empty());
+
+    assert_eq!(ids(&alice, vec![&env, GameStatus::XWins]), [game4]);
+    assert_eq!(ids(&carol, vec![&env, GameStatus::InProgress]), [game3]);
+
+    // Cancelled games are only returned when asked for
+    client.cancel_game(&game1, &alice);
+    assert_eq!(ids(&alice, vec![&env]), [game4, game2]);
+    assert_eq!(ids(&alice, vec![&env, GameStatus::Cancelled]), [game1]);
+}
+
+#[test]
+fn test_get_player_games_cursor() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    for _ in 0..3 {
+        client.create_game(&alice, &GameOptions::default());
+        client.create_game(&bob, &GameOptions::default());
+    }
+
+    let page = client.get_player_games(&alice, &vec![&env], &0, &2);
+    assert_eq!(page.games.len(), 2);
+    assert_eq!(page.games.get(0).unwrap().id, 1);
+    assert_eq!(page.games.get(1).unwrap().id, 3);
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = client.get_player_games(&alice, &vec![&env], &2, &2);
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, 5);
+    assert_eq!(page.games.get(0).unwrap().player_x, alice);
+    assert_eq!(page.next_cursor, None);
+
+    assert_eq!(
+        client.try_get_player_games(&alice, &vec![&env], &0, &0),
+        Err(Ok(Error::InvalidPageLimit))
+    );
+}
+
+#[test]
+fn test_get_player_games_scan_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    // Every game but the last is cancelled, so the first window matches nothing
+    let alice = Address::generate(&env);
+    let mut last_id = 0;
+    for _ in 0..=MAX_PLAYER_PAGE_SCAN {
+        last_id = client.create_game(&alice, &GameOptions::default());
+        if last_id <= MAX_PLAYER_PAGE_SCAN {
+            client.cancel_game(&last_id, &alice);
+        }
+    }
+
+    // Each game is its own index entry
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get(&DataKey::PlayerGameCount(alice.clone())), Some(MAX_PLAYER_PAGE_SCAN + 1));
+        assert_eq!(storage.get(&DataKey::PlayerGame(alice.clone(), MAX_PLAYER_PAGE_SCAN)), Some(last_id));
+    });
+
+    let page = client.get_player_games(&alice, &vec![&env], &0, &MAX_PAGE_LIMIT);
+    assert_eq!(page.games.len(), 0);
+    assert_eq!(page.next_cursor, Some(MAX_PLAYER_PAGE_SCAN));
+
+    let page = client.get_player_games(&alice, &vec![&env], &MAX_PLAYER_PAGE_SCAN, &MAX_PAGE_LIMIT);
+    assert_eq!(page.games.len(), 1);
+    assert_eq!(page.games.get(0).unwrap().id, last_id);
+    assert_eq!(page.next_cursor, None);
+}
+
+// === STORAGE TTL TESTS ===
+
+fn persistent_ttl(env: &Env, client: &TicTacToeContractClient, key: &DataKey) -> u32 {
+    env.as_contract(&client.address, || env.storage().persistent().get_ttl(key))
+}
+
+fn instance_ttl(env: &Env, client: &TicTacToeContractClient) -> u32 {
+    env.as_contract(&client.address, || env.storage().instance().get_ttl())
+}
+
+fn advance_ledgers(env: &Env, ledgers: u32) {
+    env.ledger().set_sequence_number(env.ledger().sequence() + ledgers);
+}
+
+#[test]
+fn test_ttl_extended_on_write() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    client.deposit_native(&player_x, &1000);
+    let game_id = client.create_game_with_bet(&player_x, &100, &TokenType::Native, &GameOptions::default());
+
+    assert_eq!(instance_ttl(&env, &client), INSTANCE_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), ACTIVE_GAME_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::GameBet(game_id)), ACTIVE_GAME_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Balance(player_x.clone())), PLAYER_DATA_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::PlayerGameCount(player_x.clone())), PLAYER_DATA_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::PlayerGame(player_x, 0)), PLAYER_DATA_TTL);
+}
+
+#[test]
+fn test_active_game_stays_live() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let options = GameOptions { move_timeout: Some(u64::MAX), ..Default::default() };
+    let game_id = client.create_game_with_bet(&player_x, &100, &TokenType::Native, &options);
+    client.join_game(&game_id, &player_o);
+
+    // Each move lands 20 days after the previous one, well past the point
+    // where an entry left at its initial TTL would have been archived
+    for (player, position) in [(&player_x, 0), (&player_o, 3), (&player_x, 1), (&player_o, 4), (&player_x, 2)] {
+        advance_ledgers(&env, 20 * DAY_IN_LEDGERS);
+        client.make_move(&game_id, player, &position);
+        assert!(persistent_ttl(&env, &client, &DataKey::Game(game_id)) > 20 * DAY_IN_LEDGERS);
+        assert_eq!(instance_ttl(&env, &client), INSTANCE_TTL);
+    }
+    assert_eq!(client.get_game(&game_id).status, GameStatus::XWins);
+
+    // The winnings are still claimable and the balance entry is live
+    advance_ledgers(&env, 20 * DAY_IN_LEDGERS);
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.get_balance(&player_x).native, 1100);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Balance(player_x)), PLAYER_DATA_TTL);
+}
+
+#[test]
+fn test_bump_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+
+    advance_ledgers(&env, 25 * DAY_IN_LEDGERS);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), ACTIVE_GAME_TTL - 25 * DAY_IN_LEDGERS);
+
+    client.bump_game(&game_id);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), ACTIVE_GAME_TTL);
+
+    assert_eq!(client.try_bump_game(&99), Err(Ok(Error::GameNotFound)));
+}
+
+#[test]
+fn test_settled_game_gets_shorter_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    client.resign(&game_id, &player_o);
+
+    // A finished game without a bet is only kept for SETTLED_GAME_TTL
+    advance_ledgers(&env, 25 * DAY_IN_LEDGERS);
+    client.bump_game(&game_id);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), SETTLED_GAME_TTL);
+}
+
+#[test]
+fn test_unclaimed_bet_keeps_game_active() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &100, &TokenType::Native, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    client.resign(&game_id, &player_o);
+
+    // Unclaimed winnings keep the full TTL
+    advance_ledgers(&env, 25 * DAY_IN_LEDGERS);
+    client.bump_game(&game_id);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), ACTIVE_GAME_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::GameBet(game_id)), ACTIVE_GAME_TTL);
+
+    // Once claimed the game is settled
+    client.claim_rewards(&game_id, &player_x);
+    advance_ledgers(&env, 25 * DAY_IN_LEDGERS);
+    client.bump_game(&game_id);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::Game(game_id)), SETTLED_GAME_TTL);
+    assert_eq!(persistent_ttl(&env, &client, &DataKey::GameBet(game_id)), SETTLED_GAME_TTL);
+}
+
+// === ADMINISTRATION TESTS ===
+
+#[test]
+fn test_constructor_sets_admin_and_schema_version() {
+    let env = Env::default();
+    let (native_token, _) = create_token_contract(&env);
+    let admin = Address::generate(&env);
+    let contract_id = env.register(TicTacToeContract, (admin.clone(), native_token.clone()));
+    let client = TicTacToeContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_native_token(), native_token);
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+}
+
+#[test]
+fn test_set_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let admin = client.get_admin();
+    let new_admin = Address::generate(&env);
+    client.set_admin(&new_admin);
+
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "set_admin"),
+                    (new_admin.clone(),).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id,
+                (symbol_short!("admin"), admin).into_val(&env),
+                new_admin.into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.get_admin(), new_admin);
+}
+
+#[test]
+fn test_set_admin_unauthorized() {
+    let env = Env::default();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let admin = client.get_admin();
+    let mallory = Address::generate(&env);
+
+    // Mallory tries to make herself admin
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_admin",
+            args: (mallory.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_set_admin(&mallory).is_err());
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_upgrade_unauthorized() {
+    let env = Env::default();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let mallory = Address::generate(&env);
+    let wasm_hash = BytesN::from_array(&env, &[1; 32]);
+
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "upgrade",
+            args: (wasm_hash.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_upgrade(&wasm_hash).is_err());
+}
+
+// === MIGRATION TESTS ===
+
+fn legacy_game(env: &Env, player_x: &Address, player_o: &Address, status: GameStatus, has_bet: bool) -> migration::GameV1 {
+    let mut board = Vec::new(env);
+    for position in 0..9 {
+        board.push_back(if position < 3 { Some(Player::X) } else { None });
+    }
+    migration::GameV1 {
+        board,
+        current_player: Player::O,
+        player_x: player_x.clone(),
+        player_o: Some(player_o.clone()),
+        status,
+        has_bet,
+        move_timeout: DEFAULT_MOVE_TIMEOUT,
+        last_move_timestamp: 1000,
+        last_move_ledger: 10,
+        draw_offer: None,
+    }
+}
+
+fn legacy_bet(amount: i128, player_x_claimed: bool, rewards_claimed: bool) -> migration::GameBetV1 {
+    migration::GameBetV1 {
+        amount,
+        token_type: TokenType::Native,
+        player_x_paid: true,
+        player_o_paid: true,
+        rewards_claimed,
+        player_x_claimed,
+        player_o_claimed: false,
+    }
+}
+
+#[test]
+fn test_migrate_legacy_games() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    // Storage as a schema 1 build left it: a game in progress, a settled win,
+    // a draw only player X has claimed and a win nobody has claimed yet
+    let in_progress = legacy_game(&env, &player_x, &player_o, GameStatus::InProgress, false);
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        storage.set(&DataKey::Game(1), &in_progress);
+        storage.set(&DataKey::Game(2), &legacy_game(&env, &player_x, &player_o, GameStatus::XWins, true));
+        storage.set(&DataKey::GameBet(2), &legacy_bet(100, true, true));
+        storage.set(&DataKey::Game(3), &legacy_game(&env, &player_x, &player_o, GameStatus::Draw, true));
+        storage.set(&DataKey::GameBet(3), &legacy_bet(200, true, false));
+        storage.set(&DataKey::Game(4), &legacy_game(&env, &player_x, &player_o, GameStatus::OWins, true));
+        storage.set(&DataKey::GameBet(4), &legacy_bet(300, false, false));
+        env.storage().instance().set(&DataKey::GameCounter, &4u32);
+        env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+    });
+
+    // Games are off limits until the migration finishes; balances are not
+    assert_eq!(client.try_get_game(&1), Err(Ok(Error::MigrationRequired)));
+    assert_eq!(client.try_create_game(&player_x, &GameOptions::default()), Err(Ok(Error::MigrationRequired)));
+    assert_eq!(client.try_get_game_bet(&2), Err(Ok(Error::MigrationRequired)));
+    assert_eq!(client.try_list_games(), Err(Ok(Error::MigrationRequired)));
+    assert_eq!(
+        client.try_list_games_page(&0, &MAX_PAGE_LIMIT, &GameFilter::any(&env)),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_get_player_games(&player_x, &Vec::new(&env), &0, &MAX_PAGE_LIMIT),
+        Err(Ok(Error::MigrationRequired))
+    );
+    client.withdraw_native(&player_x, &10);
+
+    assert_eq!(client.migrate(&1, &3), 1);
+    assert_eq!(client.get_schema_version(), 1);
+    assert_eq!(client.try_get_game(&1), Err(Ok(Error::MigrationRequired)));
+
+    assert_eq!(client.migrate(&1, &3), 0);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("migrated"), client.get_admin()).into_val(&env),
+                2u32.into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+
+    let game = client.get_game(&1);
+    assert_eq!(client.get_board(&1), in_progress.board);
+    assert_eq!(game.mode, GameMode::Classic);
+    assert!(!game.misere);
+    assert_eq!(game.shape, BoardShape::Square);
+    assert_eq!((game.size, game.win_length), (3, 3));
+    assert_eq!(game.board_x, Bytes::from_array(&env, &[0b111, 0]));
+    assert_eq!(game.current_player, Player::O);
+    assert_eq!(game.player_x, player_x);
+    assert_eq!(game.player_o, Some(player_o.clone()));
+    assert_eq!(game.status, GameStatus::InProgress);
+    assert_eq!(game.move_timeout, DEFAULT_MOVE_TIMEOUT);
+    assert_eq!(game.last_move_timestamp, 1000);
+    assert_eq!(game.last_move_ledger, 10);
+    assert_eq!(game.invited, None);
+    assert_eq!(game.join_code_hash, None);
+    assert_eq!(game.creator_side, SideChoice::X);
+    assert_eq!(game.first_move, SideChoice::X);
+    assert_eq!(game.previous_game_id, None);
+    assert_eq!(game.rematch_game_id, None);
+    assert!(game.winning_line.is_empty());
+    assert_eq!(client.get_game(&2).winning_line, vec![&env, 0, 1, 2]);
+
+    // Bets keep their claim state and stay fee-free
+    assert!(client.get_game_bet(&2).unwrap().rewards_claimed);
+    assert!(client.get_game_bet(&3).unwrap().player_x_claimed);
+    assert!(!client.get_game_bet(&4).unwrap().player_o_claimed);
+    assert_eq!(client.get_game_bet(&4).unwrap().fees, FeeConfig::default());
+
+    // Migrated games carry on as normal, recording moves from now on
+    assert!(client.get_moves(&1).is_empty());
+    client.make_move(&1, &player_o, &4);
+    assert_eq!(client.get_moves(&1).len(), 1);
+    client.claim_rewards(&3, &player_o);
+    client.claim_rewards(&4, &player_o);
+    assert!(client.get_game_bet(&3).unwrap().rewards_claimed);
+    assert!(client.get_game_bet(&4).unwrap().rewards_claimed);
+    assert_eq!(client.get_balance(&player_o).native, 1000 + 200 + 600);
+    assert_eq!(client.create_game(&player_x, &GameOptions::default()), 5);
+}
+
+#[test]
+fn test_migrate_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    // Nothing to migrate on a fresh deployment
+    assert_eq!(client.try_migrate(&SCHEMA_VERSION, &1), Err(Ok(Error::SchemaVersionMismatch)));
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+    });
+    assert_eq!(client.try_migrate(&0, &1), Err(Ok(Error::SchemaVersionMismatch)));
+    assert_eq!(client.try_migrate(&1, &0), Err(Ok(Error::InvalidBatchSize)));
+    assert_eq!(
+        client.try_migrate(&1, &(MAX_MIGRATION_BATCH + 1)),
+        Err(Ok(Error::InvalidBatchSize))
+    );
+
+    // With no games the first batch completes each step
+    assert_eq!(client.migrate(&1, &1), 0);
+    assert_eq!(client.try_migrate(&1, &1), Err(Ok(Error::SchemaVersionMismatch)));
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+}
+
+#[test]
+fn test_migrate_unauthorized() {
+    let env = Env::default();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+    let mallory = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+    });
+
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "migrate",
+            args: (1u32, 1u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_migrate(&1, &1).is_err());
+    assert_eq!(client.get_schema_version(), 1);
+}
+
+// === PLATFORM FEE TESTS ===
+
+/// Fund two players and start a native betting game between them
+fn start_betting_game(
+    env: &Env,
+    client: &TicTacToeContractClient,
+    native: &token::StellarAssetClient,
+    amount: i128,
+) -> (u32, Address, Address) {
+    start_betting_game_with(env, client, native, amount, &GameOptions::default())
+}
+
+/// `start_betting_game` with the given game options
+fn start_betting_game_with(
+    env: &Env,
+    client: &TicTacToeContractClient,
+    native: &token::StellarAssetClient,
+    amount: i128,
+    options: &GameOptions,
+) -> (u32, Address, Address) {
+    let player_x = Address::generate(env);
+    let player_o = Address::generate(env);
+    native.mint(&player_x, &amount);
+    native.mint(&player_o, &amount);
+    client.deposit_native(&player_x, &amount);
+    client.deposit_native(&player_o, &amount);
+
+    let game_id = client.create_game_with_bet(&player_x, &amount, &TokenType::Native, options);
+    client.join_game(&game_id, &player_o);
+    (game_id, player_x, player_o)
+}
+
+#[test]
+fn test_set_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    assert_eq!(client.get_fees(), FeeConfig::default());
+
+    client.set_fees(&250, &100);
+    let fees = FeeConfig { win_fee_bps: 250, draw_fee_bps: 100 };
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id,
+                (symbol_short!("fees_set"), client.get_admin()).into_val(&env),
+                fees.clone().into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.get_fees(), fees);
+
+    assert_eq!(client.try_set_fees(&(MAX_FEE_BPS + 1), &0), Err(Ok(Error::InvalidFee)));
+    assert_eq!(client.try_set_fees(&0, &(MAX_FEE_BPS + 1)), Err(Ok(Error::InvalidFee)));
+    assert_eq!(client.get_fees(), fees);
+}
+
+#[test]
+fn test_set_fees_unauthorized() {
+    let env = Env::default();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+    let mallory = Address::generate(&env);
+
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_fees",
+            args: (0u32, 0u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_set_fees(&0, &0).is_err());
+}
+
+#[test]
+fn test_win_fee_charged_and_withdrawn() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+    let native_token = client.get_native_token();
+    let native_name = token::Client::new(&env, &native_token).name();
+
+    client.set_fees(&250, &100);
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 1000);
+    client.resign(&game_id, &player_o);
+
+    // 2.5% of the 2000 pot
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("claimed"), game_id).into_val(&env),
+                (player_x.clone(), 1950i128).into_val(&env)
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("fee"), game_id).into_val(&env),
+                (native_token.clone(), 50i128).into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.get_balance(&player_x).native, 1950);
+    assert_eq!(client.get_fee_balance(&native_token), 50);
+    assert!(client.get_game_bet(&game_id).unwrap().rewards_claimed);
+
+    // The treasury is paid out from the contract's custody
+    let treasury = Address::generate(&env);
+    assert_eq!(
+        client.try_withdraw_fees(&native_token, &treasury, &51),
+        Err(Ok(Error::InsufficientFeeBalance))
+    );
+    assert_eq!(
+        client.try_withdraw_fees(&native_token, &treasury, &0),
+        Err(Ok(Error::AmountMustBePositive))
+    );
+
+    client.withdraw_fees(&native_token, &treasury, &30);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                native_token.clone(),
+                (symbol_short!("transfer"), contract_id.clone(), treasury.clone(), native_name).into_val(&env),
+                30i128.into_val(&env)
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("fee_wdraw"), treasury.clone()).into_val(&env),
+                (native_token.clone(), 30i128).into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(token::Client::new(&env, &native_token).balance(&treasury), 30);
+    assert_eq!(client.get_fee_balance(&native_token), 20);
+}
+
+#[test]
+fn test_draw_fee_charged_to_each_player() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let native_token = client.get_native_token();
+
+    client.set_fees(&250, &100);
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 250);
+    client.offer_draw(&game_id, &player_x);
+    client.accept_draw(&game_id, &player_o);
+
+    // 1% of a 250 refund is 2.5, rounded down to 2
+    client.claim_rewards(&game_id, &player_x);
+    client.claim_rewards(&game_id, &player_o);
+    assert_eq!(client.get_balance(&player_x).native, 248);
+    assert_eq!(client.get_balance(&player_o).native, 248);
+    assert_eq!(client.get_fee_balance(&native_token), 4);
+    assert!(client.get_game_bet(&game_id).unwrap().rewards_claimed);
+}
+
+#[test]
+fn test_fee_rounding() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let native_token = client.get_native_token();
+
+    // (bet, win fee bps, fee charged on the 2 * bet pot)
+    let cases = [
+        (1, 250, 0),       // 0.05 rounds down to nothing
+        (3, 1_000, 0),     // 0.6
+        (5, 1_000, 1),     // exactly 1
+        (333, 250, 16),    // 16.65
+        (10_000, 1, 2),    // 1 bps of 20_000
+        (9_999, 1, 1),     // 1.9998
+    ];
+
+    let mut total_fees = 0;
+    for (bet, bps, expected_fee) in cases {
+        client.set_fees(&bps, &0);
+        let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, bet);
+        client.resign(&game_id, &player_o);
+        client.claim_rewards(&game_id, &player_x);
+
+        assert_eq!(client.get_balance(&player_x).native, 2 * bet - expected_fee);
+        total_fees += expected_fee;
+        assert_eq!(client.get_fee_balance(&native_token), total_fees);
+    }
+}
+
+#[test]
+fn test_fees_fixed_at_game_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let native_token = client.get_native_token();
+
+    // Created while fees were zero
+    let (old_game, old_x, old_o) = start_betting_game(&env, &client, &native, 1000);
+
+    client.set_fees(&500, &0);
+    let (new_game, new_x, new_o) = start_betting_game(&env, &client, &native, 1000);
+    assert_eq!(client.get_game_bet(&new_game).unwrap().fees.win_fee_bps, 500);
+
+    client.resign(&old_game, &old_o);
+    client.resign(&new_game, &new_o);
+    client.claim_rewards(&old_game, &old_x);
+    client.claim_rewards(&new_game, &new_x);
+
+    assert_eq!(client.get_balance(&old_x).native, 2000);
+    assert_eq!(client.get_balance(&new_x).native, 1900);
+    assert_eq!(client.get_fee_balance(&native_token), 100);
+}
+
+#[test]
+fn test_token_bet_fee_accrues_per_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let (token_address, token_admin) = create_token_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    token_admin.mint(&player_x, &400);
+    token_admin.mint(&player_o, &400);
+    client.deposit_token(&player_x, &token_address, &400);
+    client.deposit_token(&player_o, &token_address, &400);
+
+    client.set_fees(&1_000, &0);
+    let game_id = client.create_game_with_bet(
+        &player_x,
+        &400,
+        &TokenType::Stellar(token_address.clone()),
+        &GameOptions::default(),
+    );
+    client.join_game(&game_id, &player_o);
+    client.resign(&game_id, &player_x);
+    client.claim_rewards(&game_id, &player_o);
+
+    assert_eq!(client.get_fee_balance(&token_address), 80);
+    assert_eq!(client.get_fee_balance(&client.get_native_token()), 0);
+
+    let treasury = Address::generate(&env);
+    client.withdraw_fees(&token_address, &treasury, &80);
+    assert_eq!(token::Client::new(&env, &token_address).balance(&treasury), 80);
+}
+
+// === PAUSE TESTS ===
+
+fn pause_everything() -> PauseFlags {
+    PauseFlags {
+        new_games: true,
+        joins: true,
+        moves: true,
+        deposits: true,
+        withdrawals: true,
+        claims: true,
+    }
+}
+
+#[test]
+fn test_set_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    assert_eq!(client.get_paused(), PauseFlags::default());
+
+    let paused = PauseFlags { deposits: true, ..Default::default() };
+    client.set_paused(&paused);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id,
+                (symbol_short!("paused"), client.get_admin()).into_val(&env),
+                paused.clone().into_val(&env)
+            )
+        ]
+    );
+    assert_eq!(client.get_paused(), paused);
+
+    client.set_paused(&PauseFlags::default());
+    assert_eq!(client.get_paused(), PauseFlags::default());
+}
+
+#[test]
+fn test_set_paused_unauthorized() {
+    let env = Env::default();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+    let mallory = Address::generate(&env);
+
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_paused",
+            args: (pause_everything(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_set_paused(&pause_everything()).is_err());
+    assert_eq!(client.get_paused(), PauseFlags::default());
+}
+
+#[test]
+fn test_pause_scopes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let native_token = client.get_native_token();
+    let (game_id, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    let open_game = client.create_game(&player_x, &GameOptions::default());
+    let joiner = Address::generate(&env);
+    native.mint(&player_x, &100);
+
+    client.set_paused(&PauseFlags { new_games: true, ..Default::default() });
+    assert_eq!(client.try_create_game(&player_x, &GameOptions::default()), Err(Ok(Error::Paused)));
+    assert_eq!(
+        client.try_create_game_with_bet(&player_x, &1, &TokenType::Native, &GameOptions::default()),
+        Err(Ok(Error::Paused))
+    );
+    client.make_move(&game_id, &player_x, &0);
+
+    client.set_paused(&PauseFlags { joins: true, ..Default::default() });
+    assert_eq!(client.try_join_game(&open_game, &joiner), Err(Ok(Error::Paused)));
+    client.make_move(&game_id, &player_o, &3);
+
+    client.set_paused(&PauseFlags { moves: true, ..Default::default() });
+    assert_eq!(client.try_make_move(&game_id, &player_x, &1), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_claim_timeout_win(&game_id, &player_o), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_resign(&game_id, &player_o), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_offer_draw(&game_id, &player_o), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_accept_draw(&game_id, &player_o), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_decline_draw(&game_id, &player_o), Err(Ok(Error::Paused)));
+    client.join_game(&open_game, &joiner);
+
+    client.set_paused(&PauseFlags { deposits: true, ..Default::default() });
+    assert_eq!(client.try_deposit_native(&player_x, &100), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_deposit_token(&player_x, &native_token, &100), Err(Ok(Error::Paused)));
+    client.resign(&game_id, &player_o);
+
+    client.set_paused(&PauseFlags { claims: true, ..Default::default() });
+    assert_eq!(client.try_claim_rewards(&game_id, &player_x), Err(Ok(Error::Paused)));
+    let unjoined = client.create_game(&player_x, &GameOptions::default());
+    assert_eq!(client.try_cancel_game(&unjoined, &player_x), Err(Ok(Error::Paused)));
+    let invite = client.create_game(&player_x, &GameOptions { opponent: Some(joiner.clone()), ..Default::default() });
+    assert_eq!(client.try_decline_invite(&invite, &joiner), Err(Ok(Error::Paused)));
+    client.deposit_native(&player_x, &100);
+
+    client.set_paused(&PauseFlags { withdrawals: true, ..Default::default() });
+    assert_eq!(client.try_withdraw_native(&player_x, &100), Err(Ok(Error::Paused)));
+    assert_eq!(client.try_withdraw_token(&player_x, &native_token, &100), Err(Ok(Error::Paused)));
+    assert_eq!(
+        client.try_withdraw_fees(&native_token, &player_x, &1),
+        Err(Ok(Error::Paused))
+    );
+    client.claim_rewards(&game_id, &player_x);
+    client.cancel_game(&unjoined, &player_x);
+    client.decline_invite(&invite, &joiner);
+
+    client.set_paused(&PauseFlags::default());
+    client.withdraw_native(&player_x, &300);
+    assert_eq!(client.get_balance(&player_x).native, 0);
+}
+
+#[test]
+fn test_withdrawals_reenabled_while_betting_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    // One settled game and one still in play
+    let (settled, player_x, player_o) = start_betting_game(&env, &client, &native, 100);
+    client.resign(&settled, &player_o);
+    client.claim_rewards(&settled, &player_x);
+    let (in_play, other_x, _) = start_betting_game(&env, &client, &native, 100);
+
+    client.set_paused(&pause_everything());
+    assert_eq!(client.try_withdraw_native(&player_x, &200), Err(Ok(Error::Paused)));
+
+    // Let players take out settled funds while everything else stays off
+    client.set_paused(&PauseFlags { withdrawals: false, ..pause_everything() });
+    client.withdraw_native(&player_x, &200);
+    assert_eq!(client.get_balance(&player_x).native, 0);
+    assert_eq!(token::Client::new(&env, &native.address).balance(&player_x), 200);
+
+    // Funds locked in the running game stay where they are
+    assert_eq!(client.try_withdraw_native(&other_x, &1), Err(Ok(Error::InsufficientBalance)));
+    assert_eq!(client.try_make_move(&in_play, &other_x, &0), Err(Ok(Error::Paused)));
+    assert!(!client.get_game_bet(&in_play).unwrap().rewards_claimed);
+}
+
+// === EVENT TESTS ===
+
+#[test]
+fn test_events_full_betting_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+    let native_name = token::Client::new(&env, &native.address).name();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    native.mint(&player_o, &1000);
+
+    // Deposits emit the asset transfer followed by our deposit event
+    client.deposit_native(&player_x, &1000);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                native.address.clone(),
+                (symbol_short!("transfer"), player_x.clone(), contract_id.clone(), native_name.clone()).into_val(&env),
+                1000i128.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("deposit"), player_x.clone()).into_val(&env),
+                (native.address.clone(), 1000i128).into_val(&env),
+            ),
+        ]
+    );
+    client.deposit_native(&player_o, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &400, &TokenType::Native, &GameOptions::default());
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("created"), game_id).into_val(&env),
+                player_x.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("bet_lock"), game_id).into_val(&env),
+                (player_x.clone(), 400i128, TokenType::Native).into_val(&env),
+            ),
+        ]
+    );
+
+    client.join_game(&game_id, &player_o);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("bet_lock"), game_id).into_val(&env),
+                (player_o.clone(), 400i128, TokenType::Native).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("joined"), game_id).into_val(&env),
+                player_o.into_val(&env),
+            ),
+        ]
+    );
+
+    let moves = [(&player_x, 0u32), (&player_o, 3), (&player_x, 1), (&player_o, 4)];
+    for (player, position) in moves {
+        client.make_move(&game_id, player, &position);
+        assert_eq!(
+            env.events().all(),
+            vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (symbol_short!("move"), game_id).into_val(&env),
+                    (player.clone(), position).into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    // The winning move also reports the final status
+    client.make_move(&game_id, &player_x, &2);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("move"), game_id).into_val(&env),
+                (player_x.clone(), 2u32).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("finished"), game_id).into_val(&env),
+                GameStatus::XWins.into_val(&env),
+            ),
+        ]
+    );
+
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("claimed"), game_id).into_val(&env),
+                (player_x.clone(), 800i128).into_val(&env),
+            ),
+        ]
+    );
+
+    client.withdraw_native(&player_x, &1400);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                native.address.clone(),
+                (symbol_short!("transfer"), contract_id.clone(), player_x.clone(), native_name).into_val(&env),
+                1400i128.into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("withdraw"), player_x.clone()).into_val(&env),
+                (native.address.clone(), 1400i128).into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_failed_move_emits_no_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+
+    assert_eq!(client.try_make_move(&game_id, &player_o, &0), Err(Ok(Error::NotYourTurn)));
+    assert_eq!(env.events().all(), vec![&env]);
+}
+
+// === AUTHORIZATION TESTS ===
+
+#[test]
+fn test_make_move_requires_player_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+    client.make_move(&game_id, &player_x, &4);
+
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            player_x.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    Symbol::new(&env, "make_move"),
+                    (game_id, player_x.clone(), 4u32).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_make_move_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    let mallory = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+
+    // Mallory signs a move on behalf of player X
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "make_move",
+            args: (game_id, player_x.clone(), 0u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_make_move(&game_id, &player_x, &0).is_err());
+    assert_eq!(client.get_board(&game_id).get(0).unwrap(), None);
+}
+
+#[test]
+fn test_join_game_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    let game_id = client.create_game(&player_x, &GameOptions::default());
+
+    env.set_auths(&[]);
+    assert!(client.try_join_game(&game_id, &player_o).is_err());
+    assert_eq!(client.get_game(&game_id).player_o, None);
+}
+
+#[test]
+fn test_withdraw_native_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+    let contract_id = client.address.clone();
+
+    let user = Address::generate(&env);
+    let mallory = Address::generate(&env);
+    native.mint(&user, &1000);
+    client.deposit_native(&user, &1000);
+
+    env.mock_auths(&[MockAuth {
+        address: &mallory,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "withdraw_native",
+            args: (user.clone(), 1000i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+
+    assert!(client.try_withdraw_native(&user, &1000).is_err());
+    assert_eq!(client.get_balance(&user).native, 1000);
+}
+
+#[test]
+fn test_claim_rewards_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+
+    native.mint(&player_x, &1000);
+    client.deposit_native(&player_x, &1000);
+    native.mint(&player_o, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let game_id = client.create_game_with_bet(&player_x, &200, &TokenType::Native, &GameOptions::default());
+    client.join_game(&game_id, &player_o);
+
+    client.make_move(&game_id, &player_x, &0);
+    client.make_move(&game_id, &player_o, &3);
+    client.make_move(&game_id, &player_x, &1);
+    client.make_move(&game_id, &player_o, &4);
+    client.make_move(&game_id, &player_x, &2);
+
+    env.set_auths(&[]);
+    assert!(client.try_claim_rewards(&game_id, &player_x).is_err());
+    assert!(!client.get_game_bet(&game_id).unwrap().player_x_claimed);
+}
+
+#[test]
+fn test_create_game_with_bet_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, native) = setup_contract(&env);
+
+    let player_x = Address::generate(&env);
+    native.mint(&player_x, &1000);
+    client.deposit_native(&player_x, &1000);
+
+    env.set_auths(&[]);
+    assert!(client
+        .try_create_game_with_bet(&player_x, &500, &TokenType::Native, &GameOptions::default())
+        .is_err());
+    assert_eq!(client.get_balance(&player_x).native, 1000);
 }
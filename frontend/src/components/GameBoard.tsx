@@ -72,10 +72,10 @@ export const GameBoard: React.FC<GameBoardProps> = ({
 
   const getUserRole = () => {
     if (!game) return 'visitor';
-    const userSymbol = walletService.getPlayerSymbol(wallet.publicKey);
+    const userAddress = wallet.publicKey;
     
-    if (game.player_x === userSymbol) return 'X';
-    if (game.player_o === userSymbol) return 'O';
+    if (game.player_x === userAddress) return 'X';
+    if (game.player_o === userAddress) return 'O';
     return 'visitor';
   };
 
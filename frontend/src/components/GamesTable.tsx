@@ -1,12 +1,11 @@
 import React, { useState, useMemo } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { FaSort, FaSortUp, FaSortDown, FaSearch } from 'react-icons/fa';
-import { GameInfo, WalletConnection, WalletService } from '../wallet';
+import { GameInfo, WalletConnection } from '../wallet';
 
 interface GamesTableProps {
   games: GameInfo[];
   wallet: WalletConnection;
-  walletService: WalletService;
   onJoinGame: (gameId: number) => Promise<void>;
   onClaimRewards: (gameId: number) => Promise<void>;
 }
@@ -18,7 +17,6 @@ type StatusFilter = 'all' | 'waiting' | 'active' | 'finished';
 export const GamesTable: React.FC<GamesTableProps> = ({
   games,
   wallet,
-  walletService,
   onJoinGame,
   onClaimRewards,
 }) => {
@@ -113,9 +111,9 @@ export const GamesTable: React.FC<GamesTableProps> = ({
   };
 
   const getPlayerActions = (game: GameInfo) => {
-    const userSymbol = walletService.getPlayerSymbol(wallet.publicKey);
-    const isPlayerX = game.player_x === userSymbol;
-    const isPlayerO = game.player_o === userSymbol;
+    const userAddress = wallet.publicKey;
+    const isPlayerX = game.player_x === userAddress;
+    const isPlayerO = game.player_o === userAddress;
     const canJoinAsPlayer = game.player_o === 'waiting' && !isPlayerX;
     const alreadyInGame = isPlayerX || isPlayerO;
     const status = getGameStatus(game);
@@ -132,9 +132,9 @@ export const ModernGamesListPage: React.FC<ModernGamesListPageProps> = ({ wallet
       }
 
       // Check if user can join as a player
-      const userSymbol = walletService.getPlayerSymbol(wallet.publicKey);
-      const isPlayerX = game.player_x === userSymbol;
-      const isPlayerO = game.player_o === userSymbol;
+      const userAddress = wallet.publicKey;
+      const isPlayerX = game.player_x === userAddress;
+      const isPlayerO = game.player_o === userAddress;
       const canJoinAsPlayer = game.player_o === 'waiting' && !isPlayerX;
       const alreadyInGame = isPlayerX || isPlayerO;
 
@@ -1,5 +1,5 @@
 import { getPublicKey, isConnected, signTransaction } from '@lobstrco/signer-extension-api';
-import { Contract, nativeToScVal, Networks, rpc, scValToNative, TransactionBuilder, xdr } from '@stellar/stellar-sdk';
+import { Address, Contract, nativeToScVal, Networks, rpc, scValToNative, TransactionBuilder, xdr } from '@stellar/stellar-sdk';
 
 export interface WalletConnection {
   publicKey: string;
@@ -9,24 +9,27 @@ export interface WalletConnection {
 export interface GameInfo {
   id: number;
   player_x: string;
-  player_o: string;
-  status: 'InProgress' | 'XWins' | 'OWins' | 'Draw' | 'Claimed';
+  player_o: string; // 'waiting' until a second player joins
+  status: 'InProgress' | 'XWins' | 'OWins' | 'Draw' | 'Claimed' | 'Cancelled';
   has_bet: boolean;
   bet_amount: number;
   bet_token_native: boolean;
-  bet_token_symbol: string;
+  bet_token: string | null; // Token contract address when the bet is not in XLM
 }
 
 export interface UserBalance {
   native: number;
-  tokens: Array<{ symbol: string; balance: number }>;
+  tokens: Array<{ symbol: string; balance: number }>; // `symbol` is the token contract address
 }
 
 export interface TokenType {
   native: boolean;
-  symbol?: string;
+  symbol?: string; // Token contract address when not native
 }
 
+// Contract enums arrive as a one-element array holding the variant name
+const variantName = (value: unknown): string => (Array.isArray(value) ? String(value[0]) : String(value));
+
 
 
 const TESTNET_NETWORK_PASSPHRASE = Networks.TESTNET;
@@ -44,30 +47,55 @@ export class WalletService {
     this.contract = new Contract(CONTRACT_ADDRESS);
   }
 
-  // Convert wallet address to a short symbol for the contract
-  private addressToSymbol(address: string): string {
-    // Create a short, deterministic identifier from the address
-    const hash = address.slice(-8); // Last 8 characters
-    return `p_${hash}`;
+  // Players and token contracts are passed to the contract as addresses
+  private addressArg(address: string): xdr.ScVal {
+    return new Address(address).toScVal();
   }
 
-  // Store address-to-symbol mapping for display purposes
-  private symbolToAddress = new Map<string, string>();
+  private unitVariant(name: string): xdr.ScVal {
+    return xdr.ScVal.scvVec([xdr.ScVal.scvSymbol(name)]);
+  }
 
-  private registerAddressSymbol(address: string): string {
-    const symbol = this.addressToSymbol(address);
-    this.symbolToAddress.set(symbol, address);
-    return symbol;
+  // Default `GameOptions`: an open 3x3 classic game where the creator plays X and moves first.
+  // Map keys must be in sorted order.
+  private defaultGameOptions(): xdr.ScVal {
+    const field = (key: string, val: xdr.ScVal) => new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val });
+    return xdr.ScVal.scvMap([
+      field('creator_side', this.unitVariant('X')),
+      field('first_move', this.unitVariant('X')),
+      field('join_code_hash', xdr.ScVal.scvVoid()),
+      field('misere', xdr.ScVal.scvBool(false)),
+      field('mode', this.unitVariant('Classic')),
+      field('move_timeout', xdr.ScVal.scvVoid()),
+      field('opponent', xdr.ScVal.scvVoid()),
+      field('shape', this.unitVariant('Square')),
+      field('size', xdr.ScVal.scvVoid()),
+      field('win_length', xdr.ScVal.scvVoid()),
+    ]);
   }
 
-  getPlayerSymbol(address: string): string {
-    return this.addressToSymbol(address);
+  // Simulate a read-only call and return its result, if any
+  private async simulateCall(wallet: WalletConnection, method: string, ...args: xdr.ScVal[]): Promise<xdr.ScVal | undefined> {
+    const account = await this.rpcServer.getAccount(wallet.publicKey);
+    const transaction = new TransactionBuilder(account, {
+      fee: '100',
+      networkPassphrase: TESTNET_NETWORK_PASSPHRASE,
+    })
+      .addOperation(this.contract.call(method, ...args))
+      .setTimeout(30)
+      .build();
+
+    const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
+    if ('error' in simulateResponse) {
+      throw new Error(`Simulation error: ${simulateResponse.error}`);
+    }
+    return simulateResponse.result?.retval;
   }
 
   // === BALANCE MANAGEMENT ===
 
   async getBalance(wallet: WalletConnection): Promise<UserBalance> {
-    console.log('📞 [CONTRACT CALL]', { method: 'get_balance', args: { user: this.getPlayerSymbol(wallet.publicKey) } });
+    console.log('📞 [CONTRACT CALL]', { method: 'get_balance', args: { user: wallet.publicKey } });
     try {
       const account = await this.rpcServer.getAccount(wallet.publicKey);
       
@@ -75,7 +103,7 @@ export class WalletService {
         fee: '100',
         networkPassphrase: TESTNET_NETWORK_PASSPHRASE,
       })
-        .addOperation(this.contract.call('get_balance', xdr.ScVal.scvSymbol(this.getPlayerSymbol(wallet.publicKey))))
+        .addOperation(this.contract.call('get_balance', this.addressArg(wallet.publicKey)))
         .setTimeout(30)
         .build();
 
@@ -92,16 +120,16 @@ export class WalletService {
       }
 
       const balanceData = scValToNative(simulateResponse.result.retval) as {
-        native?: number;
-        tokens?: Array<[string, number]>;
+        native?: bigint;
+        tokens?: Array<[string, bigint]>;
       };
       console.log('📞 [CONTRACT RESPONSE]', { method: 'get_balance', result: balanceData });
       
       const result = {
-        native: balanceData.native || 0,
+        native: Number(balanceData.native || 0),
         tokens: (balanceData.tokens || []).map((token) => ({
           symbol: token[0],
-          balance: token[1]
+          balance: Number(token[1])
         }))
       };
       
@@ -113,8 +141,8 @@ export class WalletService {
   }
 
   async depositNative(wallet: WalletConnection, amount: number): Promise<void> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'deposit_native', args: { user: playerSymbol, amount } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'deposit_native', args: { user: player, amount } });
     
     try {
       if (!isConnected()) {
@@ -130,7 +158,7 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'deposit_native',
-            xdr.ScVal.scvSymbol(playerSymbol),
+            this.addressArg(player),
             nativeToScVal(amount, { type: 'i128' })
           )
         )
@@ -140,7 +168,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: playerSymbol, amount }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: player, amount }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -150,7 +178,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: playerSymbol, amount }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: player, amount }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -164,19 +192,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'deposit_native', args: { user: playerSymbol, amount }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'deposit_native', args: { user: player, amount }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: playerSymbol, amount }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: player, amount }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: playerSymbol, amount }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'deposit_native', args: { user: player, amount }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to deposit native:', error);
@@ -185,8 +213,8 @@ export class WalletService {
   }
 
   async withdrawNative(wallet: WalletConnection, amount: number): Promise<void> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'withdraw_native', args: { user: playerSymbol, amount } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'withdraw_native', args: { user: player, amount } });
     
     try {
       if (!isConnected()) {
@@ -202,7 +230,7 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'withdraw_native',
-            xdr.ScVal.scvSymbol(playerSymbol),
+            this.addressArg(player),
             nativeToScVal(amount, { type: 'i128' })
           )
         )
@@ -212,7 +240,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: playerSymbol, amount }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: player, amount }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -222,7 +250,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: playerSymbol, amount }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: player, amount }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -236,19 +264,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'withdraw_native', args: { user: playerSymbol, amount }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'withdraw_native', args: { user: player, amount }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: playerSymbol, amount }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: player, amount }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: playerSymbol, amount }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_native', args: { user: player, amount }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to withdraw native:', error);
@@ -257,8 +285,8 @@ export class WalletService {
   }
 
   async depositToken(wallet: WalletConnection, tokenAddress: string, amount: number): Promise<void> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount } });
     
     try {
       if (!isConnected()) {
@@ -274,8 +302,8 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'deposit_token',
-            xdr.ScVal.scvSymbol(playerSymbol),
-            xdr.ScVal.scvSymbol(tokenAddress),
+            this.addressArg(player),
+            this.addressArg(tokenAddress),
             nativeToScVal(amount, { type: 'i128' })
           )
         )
@@ -285,7 +313,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -295,7 +323,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -309,19 +337,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'deposit_token', args: { user: player, token_address: tokenAddress, amount }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to deposit token:', error);
@@ -330,8 +358,8 @@ export class WalletService {
   }
 
   async withdrawToken(wallet: WalletConnection, tokenAddress: string, amount: number): Promise<void> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount } });
     
     try {
       if (!isConnected()) {
@@ -347,8 +375,8 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'withdraw_token',
-            xdr.ScVal.scvSymbol(playerSymbol),
-            xdr.ScVal.scvSymbol(tokenAddress),
+            this.addressArg(player),
+            this.addressArg(tokenAddress),
             nativeToScVal(amount, { type: 'i128' })
           )
         )
@@ -358,7 +386,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -368,7 +396,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -382,19 +410,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: playerSymbol, token_address: tokenAddress, amount }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'withdraw_token', args: { user: player, token_address: tokenAddress, amount }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to withdraw token:', error);
@@ -405,12 +433,12 @@ export class WalletService {
   // === BETTING GAMES ===
 
   async createGameWithBet(wallet: WalletConnection, betAmount: number, tokenType: TokenType): Promise<number> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    const tokenTypeParam = tokenType.native ? 
-      nativeToScVal({ Native: null }, { type: 'instance' }) :
-      nativeToScVal({ Stellar: tokenType.symbol }, { type: 'instance' });
+    const player = wallet.publicKey;
+    const tokenTypeParam = tokenType.native ?
+      this.unitVariant('Native') :
+      xdr.ScVal.scvVec([xdr.ScVal.scvSymbol('Stellar'), this.addressArg(tokenType.symbol ?? '')]);
     
-    console.log('📞 [CONTRACT CALL]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType } });
+    console.log('📞 [CONTRACT CALL]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType } });
     
     try {
       if (!isConnected()) {
@@ -426,9 +454,10 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'create_game_with_bet',
-            xdr.ScVal.scvSymbol(playerSymbol),
+            this.addressArg(player),
             nativeToScVal(betAmount, { type: 'i128' }),
-            tokenTypeParam
+            tokenTypeParam,
+            this.defaultGameOptions()
           )
         )
         .setTimeout(300)
@@ -437,7 +466,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -447,7 +476,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -463,21 +492,21 @@ export class WalletService {
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
           if ('returnValue' in statusResponse && statusResponse.returnValue) {
             const gameId = scValToNative(statusResponse.returnValue) as number;
-            console.log('📞 [CONTRACT RESPONSE]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType }, result: { game_id: gameId } });
+            console.log('📞 [CONTRACT RESPONSE]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType }, result: { game_id: gameId } });
             return gameId;
           }
           throw new Error('Failed to get game ID from transaction result');
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: playerSymbol, bet_amount: betAmount, token_type: tokenType }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'create_game_with_bet', args: { player_x: player, bet_amount: betAmount, token_type: tokenType }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to create game with bet:', error);
@@ -486,8 +515,8 @@ export class WalletService {
   }
 
   async claimRewards(wallet: WalletConnection, gameId: number): Promise<void> {
-    const playerSymbol = this.getPlayerSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'claim_rewards', args: { game_id: gameId, player: player } });
     
     try {
       if (!isConnected()) {
@@ -504,7 +533,7 @@ export class WalletService {
           this.contract.call(
             'claim_rewards',
             nativeToScVal(gameId, { type: 'u32' }),
-            xdr.ScVal.scvSymbol(playerSymbol)
+            this.addressArg(player)
           )
         )
         .setTimeout(300)
@@ -513,7 +542,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: player }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -523,7 +552,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: player }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -537,19 +566,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'claim_rewards', args: { game_id: gameId, player: player }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: player }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: playerSymbol }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'claim_rewards', args: { game_id: gameId, player: player }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to claim rewards:', error);
@@ -608,24 +637,24 @@ export class WalletService {
       const games = scValToNative(simulateResponse.result.retval) as Array<{
         id: number;
         player_x: string;
-        player_o: string;
-        status: string;
+        player_o?: string | null;
+        status: unknown;
         has_bet?: boolean;
-        bet_amount?: number;
+        bet_amount?: bigint;
         bet_token_native?: boolean;
-        bet_token_symbol?: string;
+        bet_token?: string | null;
       }>;
       console.log('📞 [CONTRACT RESPONSE]', { method: 'list_games', result: games });
       
       return games.map((game) => ({
         id: game.id,
         player_x: game.player_x,
-        player_o: game.player_o,
-        status: game.status as GameInfo['status'],
+        player_o: game.player_o ?? 'waiting',
+        status: variantName(game.status) as GameInfo['status'],
         has_bet: game.has_bet || false,
-        bet_amount: game.bet_amount || 0,
-        bet_token_native: game.bet_token_native || true,
-        bet_token_symbol: game.bet_token_symbol || 'XLM'
+        bet_amount: Number(game.bet_amount || 0),
+        bet_token_native: game.bet_token_native ?? true,
+        bet_token: game.bet_token ?? null
       }));
     } catch (error: unknown) {
       console.log('📞 [CONTRACT ERROR]', { method: 'list_games', error: error });
@@ -634,8 +663,8 @@ export class WalletService {
   }
 
   async createGame(wallet: WalletConnection): Promise<number> {
-    const playerSymbol = this.registerAddressSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'create_game', args: { player_x: playerSymbol } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'create_game', args: { player_x: player } });
     
     try {
       if (!isConnected()) {
@@ -652,7 +681,8 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'create_game',
-            xdr.ScVal.scvSymbol(playerSymbol)
+            this.addressArg(player),
+            this.defaultGameOptions()
           )
         )
         .setTimeout(300) // Longer timeout for Soroban
@@ -662,7 +692,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: playerSymbol }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: player }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -673,7 +703,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: playerSymbol }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: player }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -690,22 +720,22 @@ export class WalletService {
           // Extract game ID from transaction result
           if ('returnValue' in statusResponse && statusResponse.returnValue) {
             const gameId = scValToNative(statusResponse.returnValue) as number;
-            console.log('📞 [CONTRACT RESPONSE]', { method: 'create_game', args: { player_x: playerSymbol }, result: { game_id: gameId } });
+            console.log('📞 [CONTRACT RESPONSE]', { method: 'create_game', args: { player_x: player }, result: { game_id: gameId } });
             return gameId;
           }
-          console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: playerSymbol }, error: 'No return value found' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: player }, error: 'No return value found' });
           throw new Error('Failed to get game ID from transaction result');
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: playerSymbol }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: player }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: playerSymbol }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'create_game', args: { player_x: player }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to create game:', error);
@@ -714,8 +744,8 @@ export class WalletService {
   }
 
   async joinGame(wallet: WalletConnection, gameId: number): Promise<void> {
-    const playerSymbol = this.registerAddressSymbol(wallet.publicKey);
-    console.log('📞 [CONTRACT CALL]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol } });
+    const player = wallet.publicKey;
+    console.log('📞 [CONTRACT CALL]', { method: 'join_game', args: { game_id: gameId, player_o: player } });
     
     try {
       if (!isConnected()) {
@@ -733,7 +763,7 @@ export class WalletService {
           this.contract.call(
             'join_game',
             nativeToScVal(gameId, { type: 'u32' }),
-            xdr.ScVal.scvSymbol(playerSymbol)
+            this.addressArg(player)
           )
         )
         .setTimeout(300) // Longer timeout for Soroban
@@ -743,7 +773,7 @@ export class WalletService {
       const simulateResponse = await this.rpcServer.simulateTransaction(transaction);
       
       if ('error' in simulateResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol }, error: simulateResponse.error });
+        console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: player }, error: simulateResponse.error });
         throw new Error(`Simulation error: ${simulateResponse.error}`);
       }
 
@@ -754,7 +784,7 @@ export class WalletService {
       const transactionResponse = await this.rpcServer.sendTransaction(signedTransaction);
       
       if ('errorResult' in transactionResponse) {
-        console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol }, error: transactionResponse.errorResult });
+        console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: player }, error: transactionResponse.errorResult });
         throw new Error(`Transaction failed: ${transactionResponse.errorResult}`);
       }
 
@@ -768,19 +798,19 @@ export class WalletService {
         const statusResponse = await this.rpcServer.getTransaction(hash);
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.SUCCESS) {
-          console.log('📞 [CONTRACT RESPONSE]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol }, result: 'success' });
+          console.log('📞 [CONTRACT RESPONSE]', { method: 'join_game', args: { game_id: gameId, player_o: player }, result: 'success' });
           return;
         }
         
         if (statusResponse.status === rpc.Api.GetTransactionStatus.FAILED) {
-          console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol }, error: 'Transaction failed on network' });
+          console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: player }, error: 'Transaction failed on network' });
           throw new Error('Transaction failed');
         }
         
         attempts++;
       }
 
-      console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: playerSymbol }, error: 'Transaction timeout' });
+      console.log('📞 [CONTRACT ERROR]', { method: 'join_game', args: { game_id: gameId, player_o: player }, error: 'Transaction timeout' });
       throw new Error('Transaction timeout');
     } catch (error: unknown) {
       console.error('Failed to join game:', error);
@@ -821,14 +851,18 @@ export class WalletService {
       }
 
       const gameData = scValToNative(simulateResponse.result.retval);
-      
+
+      // The game stores its board as bitmasks; get_board expands it into cells
+      const boardValue = await this.simulateCall(wallet, 'get_board', nativeToScVal(gameId, { type: 'u32' }));
+      const cells = boardValue ? (scValToNative(boardValue) as Array<unknown>) : [];
+
       const result = {
         id: gameId,
-        board: gameData.board,
-        current_player: gameData.current_player,
+        board: cells.map((cell) => (cell == null ? null : variantName(cell) as 'X' | 'O')),
+        current_player: variantName(gameData.current_player) as 'X' | 'O',
         player_x: gameData.player_x,
-        player_o: gameData.player_o,
-        status: gameData.status
+        player_o: gameData.player_o ?? 'waiting',
+        status: variantName(gameData.status) as 'InProgress' | 'XWins' | 'OWins' | 'Draw'
       };
       
       console.log('📞 [CONTRACT RESPONSE]', { method: 'get_game', args: { game_id: gameId }, result });
@@ -854,9 +888,9 @@ export class WalletService {
         .addOperation(
           this.contract.call(
             'make_move',
-            nativeToScVal(gameId),
-            xdr.ScVal.scvSymbol(this.registerAddressSymbol(wallet.publicKey)),
-            nativeToScVal(position)
+            nativeToScVal(gameId, { type: 'u32' }),
+            this.addressArg(wallet.publicKey),
+            nativeToScVal(position, { type: 'u32' })
           )
         )
         .setTimeout(300)
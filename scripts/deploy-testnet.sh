@@ -24,11 +24,18 @@ WASM_HASH=$(stellar contract install \
 
 echo "📝 WASM Hash: $WASM_HASH"
 
+# Native XLM Stellar Asset Contract used for escrow deposits
+NATIVE_TOKEN=$(stellar contract id asset --asset native --network testnet)
+echo "📝 Native token: $NATIVE_TOKEN"
+
 echo "🚀 Deploying contract instance..."
 CONTRACT_ID=$(stellar contract deploy \
     --wasm-hash $WASM_HASH \
     --source deployer \
-    --network testnet)
+    --network testnet \
+    -- \
+    --admin deployer \
+    --native_token $NATIVE_TOKEN)
 
 echo "✅ Contract deployed successfully to testnet!"
 echo "📝 Contract ID: $CONTRACT_ID"
@@ -30,11 +30,18 @@ WASM_HASH=$(stellar contract upload \
 
 echo "📝 WASM Hash: $WASM_HASH"
 
+# Native XLM Stellar Asset Contract used for escrow deposits
+NATIVE_TOKEN=$(stellar contract id asset --asset native --network local)
+echo "📝 Native token: $NATIVE_TOKEN"
+
 echo "🚀 Deploying contract instance..."
 CONTRACT_ID=$(stellar contract deploy \
     --wasm-hash $WASM_HASH \
     --source alice \
-    --network local)
+    --network local \
+    -- \
+    --admin alice \
+    --native_token $NATIVE_TOKEN)
 
 echo "✅ Contract deployed successfully!"
 echo "📝 Contract ID: $CONTRACT_ID"
@@ -1,17 +1,24 @@
-use soroban_sdk::{symbol_short, Env};
-use tic_tac_toe::{GameStatus, Player, TicTacToeContract, TicTacToeContractClient};
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use tic_tac_toe::{GameOptions, GameStatus, Player, TicTacToeContract, TicTacToeContractClient};
+
+fn setup_contract<'a>(env: &Env) -> TicTacToeContractClient<'a> {
+    let native_token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    let admin = Address::generate(env);
+    let contract_id = env.register(TicTacToeContract, (admin, native_token.address()));
+    TicTacToeContractClient::new(env, &contract_id)
+}
 
 #[test]
 fn test_full_game_workflow() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let client = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
     // Create a new game
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     assert_eq!(game_id, 1);
 
     // Join the game with second player
@@ -20,7 +27,7 @@ fn test_full_game_workflow() {
     // Verify initial game state
     let game = client.get_game(&game_id);
     assert_eq!(game.player_x, player_x);
-    assert_eq!(game.player_o, player_o);
+    assert_eq!(game.player_o, Some(player_o.clone()));
     assert_eq!(game.current_player, Player::X);
     assert_eq!(game.status, GameStatus::InProgress);
 
@@ -69,27 +76,34 @@ fn test_full_game_workflow() {
 #[test]
 fn test_multiple_concurrent_games() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let client = setup_contract(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let charlie = Address::generate(&env);
+    let david = Address::generate(&env);
+    let eve = Address::generate(&env);
+    let frank = Address::generate(&env);
 
     // Create multiple games
-    let game1_id = client.create_game(&symbol_short!("alice"));
-    let game2_id = client.create_game(&symbol_short!("charlie"));
-    let game3_id = client.create_game(&symbol_short!("eve"));
+    let game1_id = client.create_game(&alice, &GameOptions::default());
+    let game2_id = client.create_game(&charlie, &GameOptions::default());
+    let game3_id = client.create_game(&eve, &GameOptions::default());
 
     assert_eq!(game1_id, 1);
     assert_eq!(game2_id, 2);
     assert_eq!(game3_id, 3);
 
     // Join the games with second players
-    client.join_game(&game1_id, &symbol_short!("bob"));
-    client.join_game(&game2_id, &symbol_short!("david"));
-    client.join_game(&game3_id, &symbol_short!("frank"));
+    client.join_game(&game1_id, &bob);
+    client.join_game(&game2_id, &david);
+    client.join_game(&game3_id, &frank);
 
     // Make moves in different games
-    client.make_move(&game1_id, &symbol_short!("alice"), &0);
-    client.make_move(&game2_id, &symbol_short!("charlie"), &4);
-    client.make_move(&game3_id, &symbol_short!("eve"), &8);
+    client.make_move(&game1_id, &alice, &0);
+    client.make_move(&game2_id, &charlie, &4);
+    client.make_move(&game3_id, &eve, &8);
 
     // Verify each game maintains its own state
     let board1 = client.get_board(&game1_id);
@@ -112,14 +126,14 @@ fn test_multiple_concurrent_games() {
 #[test]
 fn test_game_persistence() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let client = setup_contract(&env);
 
-    let player_x = symbol_short!("alice");
-    let player_o = symbol_short!("bob");
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
 
     // Create and play several moves
-    let game_id = client.create_game(&player_x);
+    let game_id = client.create_game(&player_x, &GameOptions::default());
     client.join_game(&game_id, &player_o);
     client.make_move(&game_id, &player_x, &0);
     client.make_move(&game_id, &player_o, &1);
@@ -132,7 +146,7 @@ fn test_game_persistence() {
     let board2 = client.get_board(&game_id);
 
     // All retrievals should return identical state
-    assert_eq!(game1.board, game2.board);
+    assert_eq!((game1.board_x, game1.board_o), (game2.board_x, game2.board_o));
     assert_eq!(game1.current_player, game2.current_player);
     assert_eq!(game1.status, game2.status);
     assert_eq!(board1, board2);
@@ -147,25 +161,28 @@ fn test_game_persistence() {
 #[test]
 fn test_winning_conditions_comprehensive() {
     let env = Env::default();
-    let contract_id = env.register(TicTacToeContract, ());
-    let client = TicTacToeContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    let client = setup_contract(&env);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
 
     // Test all possible winning conditions for X
 
     // Row wins
     for row in 0..3 {
-        let game_id = client.create_game(&symbol_short!("alice"));
-        client.join_game(&game_id, &symbol_short!("bob"));
+        let game_id = client.create_game(&alice, &GameOptions::default());
+        client.join_game(&game_id, &bob);
 
         // X wins the row, O plays other positions
         for col in 0..3 {
             let pos = row * 3 + col;
-            client.make_move(&game_id, &symbol_short!("alice"), &pos);
+            client.make_move(&game_id, &alice, &pos);
 
             if col < 2 {
                 // Don't make O move after winning move
                 let o_pos = ((row + 1) % 3) * 3 + col;
-                client.make_move(&game_id, &symbol_short!("bob"), &o_pos);
+                client.make_move(&game_id, &bob, &o_pos);
             }
         }
 
@@ -175,17 +192,17 @@ fn test_winning_conditions_comprehensive() {
 
     // Column wins
     for col in 0..3 {
-        let game_id = client.create_game(&symbol_short!("alice"));
-        client.join_game(&game_id, &symbol_short!("bob"));
+        let game_id = client.create_game(&alice, &GameOptions::default());
+        client.join_game(&game_id, &bob);
 
         for row in 0..3 {
             let pos = row * 3 + col;
-            client.make_move(&game_id, &symbol_short!("alice"), &pos);
+            client.make_move(&game_id, &alice, &pos);
 
             if row < 2 {
                 // Don't make O move after winning move
                 let o_pos = row * 3 + ((col + 1) % 3);
-                client.make_move(&game_id, &symbol_short!("bob"), &o_pos);
+                client.make_move(&game_id, &bob, &o_pos);
             }
         }
 
@@ -195,25 +212,25 @@ fn test_winning_conditions_comprehensive() {
 
     // Diagonal wins
     // Main diagonal (0, 4, 8)
-    let game_id = client.create_game(&symbol_short!("alice"));
-    client.join_game(&game_id, &symbol_short!("bob"));
-    client.make_move(&game_id, &symbol_short!("alice"), &0);
-    client.make_move(&game_id, &symbol_short!("bob"), &1);
-    client.make_move(&game_id, &symbol_short!("alice"), &4);
-    client.make_move(&game_id, &symbol_short!("bob"), &2);
-    client.make_move(&game_id, &symbol_short!("alice"), &8);
+    let game_id = client.create_game(&alice, &GameOptions::default());
+    client.join_game(&game_id, &bob);
+    client.make_move(&game_id, &alice, &0);
+    client.make_move(&game_id, &bob, &1);
+    client.make_move(&game_id, &alice, &4);
+    client.make_move(&game_id, &bob, &2);
+    client.make_move(&game_id, &alice, &8);
 
     let game = client.get_game(&game_id);
     assert_eq!(game.status, GameStatus::XWins);
 
     // Anti-diagonal (2, 4, 6)
-    let game_id = client.create_game(&symbol_short!("alice"));
-    client.join_game(&game_id, &symbol_short!("bob"));
-    client.make_move(&game_id, &symbol_short!("alice"), &2);
-    client.make_move(&game_id, &symbol_short!("bob"), &0);
-    client.make_move(&game_id, &symbol_short!("alice"), &4);
-    client.make_move(&game_id, &symbol_short!("bob"), &1);
-    client.make_move(&game_id, &symbol_short!("alice"), &6);
+    let game_id = client.create_game(&alice, &GameOptions::default());
+    client.join_game(&game_id, &bob);
+    client.make_move(&game_id, &alice, &2);
+    client.make_move(&game_id, &bob, &0);
+    client.make_move(&game_id, &alice, &4);
+    client.make_move(&game_id, &bob, &1);
+    client.make_move(&game_id, &alice, &6);
 
     let game = client.get_game(&game_id);
     assert_eq!(game.status, GameStatus::XWins);
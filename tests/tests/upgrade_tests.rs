@@ -0,0 +1,104 @@
+//! Upgrade tests run against the optimized contract WASM, so build it first
+//! with `stellar contract build`.
+use soroban_sdk::{testutils::Address as _, token, Address, Bytes, Env};
+
+mod tic_tac_toe_wasm {
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/tic_tac_toe.wasm");
+}
+
+use tic_tac_toe_wasm::{
+    BoardShape, Client, GameMode, GameOptions, GameStatus, Player, SideChoice, TokenType,
+};
+
+/// The contract WASM with an extra custom section appended: identical code
+/// under a different hash, standing in for the next release
+fn next_release_wasm(env: &Env) -> Bytes {
+    let name = b"upgrade-test";
+    let mut wasm = Bytes::from_slice(env, tic_tac_toe_wasm::WASM);
+    wasm.push_back(0); // custom section id
+    wasm.push_back(name.len() as u8 + 1); // section size
+    wasm.push_back(name.len() as u8);
+    wasm.extend_from_slice(name);
+    wasm
+}
+
+#[test]
+fn test_upgrade_preserves_games_and_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let native = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let native_admin = token::StellarAssetClient::new(&env, &native.address());
+    let admin = Address::generate(&env);
+    let contract_id = env.register(tic_tac_toe_wasm::WASM, (admin.clone(), native.address()));
+    let client = Client::new(&env, &contract_id);
+
+    let player_x = Address::generate(&env);
+    let player_o = Address::generate(&env);
+    native_admin.mint(&player_x, &1000);
+    native_admin.mint(&player_o, &1000);
+    client.deposit_native(&player_x, &1000);
+    client.deposit_native(&player_o, &1000);
+
+    let options = GameOptions {
+        move_timeout: None,
+        opponent: None,
+        join_code_hash: None,
+        creator_side: SideChoice::X,
+        first_move: SideChoice::X,
+        size: None,
+        win_length: None,
+        mode: GameMode::Classic,
+        misere: false,
+        shape: BoardShape::Square,
+    };
+    let game_id = client.create_game_with_bet(&player_x, &100, &TokenType::Native, &options);
+    client.join_game(&game_id, &player_o);
+    client.make_move(&game_id, &player_x, &4);
+
+    let game_before = client.get_game(&game_id);
+    let balance_before = client.get_balance(&player_x);
+
+    let new_wasm_hash = env.deployer().upload_contract_wasm(next_release_wasm(&env));
+    client.upgrade(&new_wasm_hash);
+
+    // Same contract ID, same data
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_schema_version(), tic_tac_toe::SCHEMA_VERSION);
+    assert_eq!(client.get_game(&game_id), game_before);
+    assert_eq!(client.get_balance(&player_x), balance_before);
+    assert_eq!(balance_before.native, 900);
+
+    // The game can be finished and settled on the new code
+    client.make_move(&game_id, &player_o, &0);
+    client.make_move(&game_id, &player_x, &2);
+    client.make_move(&game_id, &player_o, &1);
+    let game = client.make_move(&game_id, &player_x, &6);
+    assert_eq!(game.status, GameStatus::XWins);
+    assert_eq!(client.get_board(&game_id).get(4).unwrap(), Some(Player::X));
+
+    client.claim_rewards(&game_id, &player_x);
+    assert_eq!(client.get_balance(&player_x).native, 1100);
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let env = Env::default();
+
+    let native = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let admin = Address::generate(&env);
+    let contract_id = env.register(tic_tac_toe_wasm::WASM, (admin.clone(), native.address()));
+    let client = Client::new(&env, &contract_id);
+
+    let new_wasm_hash = env.deployer().upload_contract_wasm(next_release_wasm(&env));
+
+    // No auth at all
+    assert!(client.try_upgrade(&new_wasm_hash).is_err());
+
+    // After handing over the role only the new admin can upgrade
+    let new_admin = Address::generate(&env);
+    env.mock_all_auths();
+    client.set_admin(&new_admin);
+    client.upgrade(&new_wasm_hash);
+    assert_eq!(env.auths()[0].0, new_admin);
+}